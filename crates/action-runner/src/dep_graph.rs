@@ -3,17 +3,24 @@ use crate::node::Node;
 use moon_config::ProjectLanguage;
 use moon_lang::SupportedLanguage;
 use moon_logger::{color, debug, map_list, trace};
-use moon_project::{Project, ProjectGraph, Target, TargetError, TargetProject, TouchedFilePaths};
+use moon_project::{
+    Project, ProjectError, ProjectGraph, Target, TargetError, TargetID, TargetProject, Task,
+    TouchedFilePaths,
+};
+use moon_utils::graph::{self, GraphEdges, GraphNodes};
 use petgraph::algo::toposort;
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::DiGraph;
 use petgraph::visit::EdgeRef;
+use petgraph::Direction;
 use petgraph::Graph;
+use serde::Serialize;
 use std::collections::{HashMap, HashSet};
 
 pub use petgraph::graph::NodeIndex;
 
 const TARGET: &str = "moon:dep-graph";
+const JSON_VERSION: u8 = 1;
 
 fn get_lang_from_project(project: &Project) -> SupportedLanguage {
     match &project.config.language {
@@ -25,6 +32,106 @@ fn get_lang_from_project(project: &Project) -> SupportedLanguage {
 pub type DepGraphType = DiGraph<Node, ()>;
 pub type BatchedTopoSort = Vec<Vec<NodeIndex>>;
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DepGraphNodeType {
+    InstallDeps,
+    RunTarget,
+    SetupToolchain,
+    SyncProject,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DepGraphEdgeType {
+    /// Target requires the language's dependencies to be installed.
+    InstallDeps,
+
+    /// Project is synced after the projects it depends on.
+    ProjectDependency,
+
+    /// Action requires the toolchain to be setup.
+    SetupToolchain,
+
+    /// Target requires its project to be synced.
+    SyncProject,
+
+    /// Target runs after the targets in its task's `deps`.
+    TaskDependency,
+}
+
+impl DepGraphEdgeType {
+    fn from_nodes(source: &Node, target: &Node) -> Self {
+        match (source, target) {
+            (_, Node::SetupToolchain) => DepGraphEdgeType::SetupToolchain,
+            (_, Node::InstallDeps(_)) => DepGraphEdgeType::InstallDeps,
+            (Node::SyncProject(_, _), Node::SyncProject(_, _)) => {
+                DepGraphEdgeType::ProjectDependency
+            }
+            (_, Node::SyncProject(_, _)) => DepGraphEdgeType::SyncProject,
+            (_, Node::RunTarget(_)) => DepGraphEdgeType::TaskDependency,
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            DepGraphEdgeType::InstallDeps => "installDeps",
+            DepGraphEdgeType::ProjectDependency => "projectDependency",
+            DepGraphEdgeType::SetupToolchain => "setupToolchain",
+            DepGraphEdgeType::SyncProject => "syncProject",
+            DepGraphEdgeType::TaskDependency => "taskDependency",
+        }
+        .into()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepGraphEdge {
+    pub source: usize,
+
+    pub target: usize,
+
+    #[serde(rename = "type")]
+    pub type_of: DepGraphEdgeType,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepGraphNode {
+    /// Index of the node in the graph, as referenced by edges.
+    pub id: usize,
+
+    pub label: String,
+
+    /// Language of install and sync actions.
+    pub language: Option<String>,
+
+    /// Project being synced.
+    pub project: Option<Project>,
+
+    /// Target being ran.
+    pub target: Option<TargetID>,
+
+    /// Task of the target being ran.
+    pub task: Option<Task>,
+
+    #[serde(rename = "type")]
+    pub type_of: DepGraphNodeType,
+}
+
+/// A stable representation of the dependency graph, that can be consumed by other tools.
+/// Nodes and edges are sorted by index, and the version is bumped on breaking changes.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepGraphJson {
+    pub edges: Vec<DepGraphEdge>,
+
+    pub nodes: Vec<DepGraphNode>,
+
+    pub version: u8,
+}
+
 /// A directed acyclic graph (DAG) for the work that needs to be processed, based on a
 /// project or task's dependency chain. This is also known as a "task graph" (not to
 /// be confused with ours) or a "dependency graph".
@@ -47,6 +154,18 @@ impl DepGraph {
         }
     }
 
+    /// Return the nodes that directly depend on the provided node, in index order.
+    pub fn get_dependents_of_index(&self, index: &NodeIndex) -> Vec<NodeIndex> {
+        let mut dependents = self
+            .graph
+            .neighbors_directed(*index, Direction::Incoming)
+            .collect::<Vec<_>>();
+
+        dependents.sort();
+        dependents.dedup();
+        dependents
+    }
+
     pub fn get_index_from_node(&self, node: &Node) -> Option<&NodeIndex> {
         self.indices.get(node)
     }
@@ -102,8 +221,23 @@ impl DepGraph {
         projects: &ProjectGraph,
         touched_files: Option<&TouchedFilePaths>,
     ) -> Result<usize, DepGraphError> {
+        Ok(self
+            .run_target_from_project(target, None, projects, touched_files)?
+            .len())
+    }
+
+    /// Insert a target to run, and return the IDs of all targets that were inserted.
+    /// The deps (^) and self (~) scopes are resolved relative to the provided project,
+    /// typically the project of the current working directory.
+    pub fn run_target_from_project(
+        &mut self,
+        target: &Target,
+        from_project: Option<&Project>,
+        projects: &ProjectGraph,
+        touched_files: Option<&TouchedFilePaths>,
+    ) -> Result<Vec<TargetID>, DepGraphError> {
         let task_id = &target.task_id;
-        let mut inserted_count = 0;
+        let mut inserted_targets = vec![];
 
         match &target.project {
             // :task
@@ -116,13 +250,26 @@ impl DepGraph {
                             .insert_target(&project_id, task_id, projects, touched_files)?
                             .is_some()
                     {
-                        inserted_count += 1;
+                        inserted_targets.push(Target::format(&project_id, task_id)?);
                     }
                 }
             }
             // ^:task
             TargetProject::Deps => {
-                target.fail_with(TargetError::NoProjectDepsInRunContext)?;
+                let project = from_project
+                    .ok_or(ProjectError::Target(TargetError::NoProjectDepsInRunContext))?;
+
+                for dep_id in projects.get_dependencies_of(project)? {
+                    let dep = projects.load(&dep_id)?;
+
+                    if dep.tasks.contains_key(task_id)
+                        && self
+                            .insert_target(&dep_id, task_id, projects, touched_files)?
+                            .is_some()
+                    {
+                        inserted_targets.push(Target::format(&dep_id, task_id)?);
+                    }
+                }
             }
             // project:task
             TargetProject::Id(project_id) => {
@@ -130,16 +277,39 @@ impl DepGraph {
                     .insert_target(project_id, task_id, projects, touched_files)?
                     .is_some()
                 {
-                    inserted_count += 1;
+                    inserted_targets.push(Target::format(project_id, task_id)?);
                 }
             }
             // ~:task
             TargetProject::Own => {
-                target.fail_with(TargetError::NoProjectSelfInRunContext)?;
+                let project = from_project
+                    .ok_or(ProjectError::Target(TargetError::NoProjectSelfInRunContext))?;
+
+                if self
+                    .insert_target(&project.id, task_id, projects, touched_files)?
+                    .is_some()
+                {
+                    inserted_targets.push(Target::format(&project.id, task_id)?);
+                }
+            }
+            // #tag:task
+            TargetProject::Tag(tag) => {
+                for project_id in projects.ids() {
+                    let project = projects.load(&project_id)?;
+
+                    if project.config.tags.contains(tag)
+                        && project.tasks.contains_key(task_id)
+                        && self
+                            .insert_target(&project_id, task_id, projects, touched_files)?
+                            .is_some()
+                    {
+                        inserted_targets.push(Target::format(&project_id, task_id)?);
+                    }
+                }
             }
         };
 
-        Ok(inserted_count)
+        Ok(inserted_targets)
     }
 
     pub fn run_target_dependents(
@@ -308,6 +478,111 @@ impl DepGraph {
         format!("{:?}", dot)
     }
 
+    /// Format as a GraphML document.
+    pub fn to_graphml(&self) -> String {
+        let (nodes, edges) = self.get_labeled_nodes_and_edges();
+
+        graph::to_graphml(&nodes, &edges)
+    }
+
+    /// Format as a JSON serializable structure, with the metadata
+    /// of each project being synced, and each task being ran.
+    pub fn to_json(&self, projects: &ProjectGraph) -> Result<DepGraphJson, DepGraphError> {
+        let mut nodes = vec![];
+
+        for index in self.graph.node_indices() {
+            let node = &self.graph[index];
+            let mut json_node = DepGraphNode {
+                id: index.index(),
+                label: node.label(),
+                language: None,
+                project: None,
+                target: None,
+                task: None,
+                type_of: DepGraphNodeType::SetupToolchain,
+            };
+
+            match node {
+                Node::InstallDeps(lang) => {
+                    json_node.language = Some(lang.to_string().to_lowercase());
+                    json_node.type_of = DepGraphNodeType::InstallDeps;
+                }
+                Node::RunTarget(target_id) => {
+                    let (project_id, task_id) = Target::parse(target_id)?.ids()?;
+                    let project = projects.load(&project_id)?;
+
+                    json_node.target = Some(target_id.clone());
+                    json_node.task = Some(project.get_task(&task_id)?.clone());
+                    json_node.type_of = DepGraphNodeType::RunTarget;
+                }
+                Node::SetupToolchain => {}
+                Node::SyncProject(lang, project_id) => {
+                    json_node.language = Some(lang.to_string().to_lowercase());
+                    json_node.project = Some(projects.load(project_id)?);
+                    json_node.type_of = DepGraphNodeType::SyncProject;
+                }
+            };
+
+            nodes.push(json_node);
+        }
+
+        let mut edges = self
+            .graph
+            .raw_edges()
+            .iter()
+            .map(|edge| DepGraphEdge {
+                source: edge.source().index(),
+                target: edge.target().index(),
+                type_of: DepGraphEdgeType::from_nodes(
+                    &self.graph[edge.source()],
+                    &self.graph[edge.target()],
+                ),
+            })
+            .collect::<Vec<_>>();
+
+        edges.sort_by(|a, b| a.source.cmp(&b.source).then(a.target.cmp(&b.target)));
+
+        Ok(DepGraphJson {
+            edges,
+            nodes,
+            version: JSON_VERSION,
+        })
+    }
+
+    /// Format as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let (nodes, edges) = self.get_labeled_nodes_and_edges();
+
+        graph::to_mermaid(&nodes, &edges)
+    }
+
+    fn get_labeled_nodes_and_edges(&self) -> (GraphNodes, GraphEdges) {
+        let nodes = self
+            .graph
+            .node_indices()
+            .map(|index| (format!("n{}", index.index()), self.graph[index].label()))
+            .collect();
+
+        let edges = self
+            .graph
+            .raw_edges()
+            .iter()
+            .map(|edge| {
+                (
+                    format!("n{}", edge.source().index()),
+                    format!("n{}", edge.target().index()),
+                    DepGraphEdgeType::from_nodes(
+                        &self.graph[edge.source()],
+                        &self.graph[edge.target()],
+                    )
+                    .label(),
+                )
+            })
+            .collect();
+
+        (nodes, edges)
+    }
+
     #[track_caller]
     fn detect_cycle(&self) -> Result<(), DepGraphError> {
         use petgraph::algo::kosaraju_scc;
@@ -383,9 +658,23 @@ impl DepGraph {
 
             for dep_target_id in &task.deps {
                 let dep_target = Target::parse(dep_target_id)?;
+                let dep_project_id = dep_target.project_id.unwrap();
+
+                // Persistent tasks never complete, so nothing can wait on them
+                if projects
+                    .load(&dep_project_id)?
+                    .get_task(&dep_target.task_id)?
+                    .options
+                    .persistent
+                {
+                    return Err(DepGraphError::PersistentDependency(
+                        target_id,
+                        dep_target_id.to_owned(),
+                    ));
+                }
 
                 if let Some(run_dep_target_index) = self.insert_target(
-                    &dep_target.project_id.unwrap(),
+                    &dep_project_id,
                     &dep_target.task_id,
                     projects,
                     touched_files,
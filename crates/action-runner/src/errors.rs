@@ -26,6 +26,11 @@ pub enum DepGraphError {
     #[error("A dependency cycle has been detected for <file>{0}</file>.")]
     CycleDetected(String),
 
+    #[error(
+        "Target <target>{0}</target> cannot depend on <target>{1}</target>, as it's a persistent task that never completes."
+    )]
+    PersistentDependency(String, String),
+
     #[error("Unknown node {0} found in dependency graph. How did this get here?")]
     UnknownNode(usize),
 
@@ -1,9 +1,13 @@
 mod dep_graph;
 mod errors;
 mod node;
+mod report;
 mod runner;
+mod trace;
 
 pub use dep_graph::*;
 pub use errors::*;
 pub use node::Node;
+pub use report::*;
 pub use runner::*;
+pub use trace::*;
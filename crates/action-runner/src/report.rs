@@ -0,0 +1,130 @@
+use moon_action::{Action, ActionContext, ActionStatus};
+use moon_cache::to_millis;
+use moon_utils::path;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReportAttempt {
+    /// Duration in milliseconds.
+    pub duration: Option<u128>,
+
+    pub index: u8,
+
+    /// Log file of the attempt's output.
+    pub log_path: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReportAction<'a> {
+    pub attempts: Vec<RunReportAttempt>,
+
+    pub cache_miss_reasons: Option<&'a Vec<String>>,
+
+    pub cached: bool,
+
+    /// Duration in milliseconds.
+    pub duration: Option<u128>,
+
+    pub error: Option<&'a String>,
+
+    pub hash: Option<&'a String>,
+
+    pub label: Option<&'a String>,
+
+    pub skip_reason: Option<&'a String>,
+
+    pub status: &'a ActionStatus,
+}
+
+impl<'a> RunReportAction<'a> {
+    pub fn new(action: &'a Action) -> Self {
+        RunReportAction {
+            attempts: action
+                .attempts
+                .as_ref()
+                .map(|attempts| {
+                    attempts
+                        .iter()
+                        .map(|attempt| RunReportAttempt {
+                            duration: attempt.duration.map(|d| d.as_millis()),
+                            index: attempt.index,
+                            log_path: attempt
+                                .log_path
+                                .as_ref()
+                                .map(|log_path| log_path.to_string_lossy().to_string()),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            cache_miss_reasons: action.cache_miss_reasons.as_ref(),
+            cached: matches!(action.status, ActionStatus::Cached),
+            duration: action.duration.map(|d| d.as_millis()),
+            error: action.error.as_ref(),
+            hash: action.hash.as_ref(),
+            label: action.label.as_ref(),
+            skip_reason: action.skip_reason.as_ref(),
+            status: &action.status,
+        }
+    }
+}
+
+/// A report of a pipeline run, written to `.moon/cache/runReport.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReport<'a> {
+    pub actions: Vec<RunReportAction<'a>>,
+
+    /// Duration in milliseconds.
+    pub duration: u128,
+
+    /// The signal that interrupted the run, if any.
+    pub interrupted: Option<String>,
+
+    pub primary_targets: Vec<&'a String>,
+
+    /// Start time in milliseconds since the epoch.
+    pub start_time: u128,
+
+    /// Touched files, relative from the workspace root.
+    pub touched_files: Vec<String>,
+}
+
+impl<'a> RunReport<'a> {
+    pub fn new(
+        actions: &'a [Action],
+        context: &'a ActionContext,
+        workspace_root: &Path,
+        start_time: SystemTime,
+        duration: Duration,
+        interrupted: Option<String>,
+    ) -> Self {
+        let mut primary_targets = context.primary_targets.iter().collect::<Vec<_>>();
+        primary_targets.sort();
+
+        let mut touched_files = context
+            .touched_files
+            .iter()
+            .map(|file| {
+                path::standardize_separators(
+                    file.strip_prefix(workspace_root)
+                        .unwrap_or(file)
+                        .to_string_lossy(),
+                )
+            })
+            .collect::<Vec<_>>();
+        touched_files.sort();
+
+        RunReport {
+            actions: actions.iter().map(RunReportAction::new).collect(),
+            duration: duration.as_millis(),
+            interrupted,
+            primary_targets,
+            start_time: to_millis(start_time),
+            touched_files,
+        }
+    }
+}
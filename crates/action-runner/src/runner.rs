@@ -1,20 +1,64 @@
-use crate::dep_graph::DepGraph;
+use crate::dep_graph::{DepGraph, NodeIndex};
 use crate::errors::{ActionRunnerError, DepGraphError};
 use crate::node::Node;
+use crate::report::RunReport;
+use crate::trace::RunTrace;
 use moon_action::{
     install_node_deps, run_target, setup_toolchain, sync_node_project, Action, ActionContext,
-    ActionStatus,
+    ActionError, ActionStatus,
 };
+use moon_error::MoonError;
 use moon_lang::SupportedLanguage;
-use moon_logger::{color, debug, error, trace};
+use moon_logger::{color, debug, error, trace, warn};
+use moon_project::Target;
+use moon_utils::process::{signal_running_processes, Signal};
 use moon_workspace::Workspace;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use tokio::task;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, RwLock, Semaphore};
+use tokio::{signal, task, time};
 
 const LOG_TARGET: &str = "moon:action-runner";
 
+// How long to wait for in-flight actions to exit after being signaled,
+// before forcefully killing their processes.
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+// How long to wait for killed processes to be reaped.
+const SHUTDOWN_KILL_PERIOD: Duration = Duration::from_secs(1);
+
+fn get_default_concurrency() -> usize {
+    thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() -> Signal {
+    use tokio::signal::unix::{signal as unix_signal, SignalKind};
+
+    let mut sigterm =
+        unix_signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM signal");
+
+    tokio::select! {
+        _ = signal::ctrl_c() => Signal::Interrupt,
+        _ = sigterm.recv() => Signal::Terminate,
+    }
+}
+
+#[cfg(windows)]
+async fn wait_for_shutdown_signal() -> Signal {
+    signal::ctrl_c()
+        .await
+        .expect("Failed to listen for Ctrl+C signal");
+
+    Signal::Interrupt
+}
+
 async fn run_action(
     node: &Node,
     action: &mut Action,
@@ -40,6 +84,12 @@ async fn run_action(
         Ok(status) => {
             action.pass(status);
         }
+        Err(ActionError::Moon(error @ MoonError::ProcessTimeout(_, _))) => {
+            action.time_out(error.to_string());
+        }
+        Err(error @ ActionError::Interrupted(_)) => {
+            action.interrupt(error.to_string());
+        }
         Err(error) => {
             action.fail(error.to_string());
 
@@ -53,11 +103,102 @@ async fn run_action(
     Ok(())
 }
 
+async fn run_node(
+    node_index: NodeIndex,
+    graph: Arc<RwLock<DepGraph>>,
+    context: Arc<ActionContext>,
+    workspace: Arc<RwLock<Workspace>>,
+    semaphore: Option<Arc<Semaphore>>,
+    trace: Option<Arc<RunTrace>>,
+) -> Result<Action, ActionRunnerError> {
+    // Wait for a slot to open up before running, so that we never spawn more
+    // processes than the concurrency allows. Persistent actions never complete,
+    // so they do not take a slot, otherwise they would block all other actions.
+    let _permit = match semaphore {
+        Some(semaphore) => Some(
+            semaphore
+                .acquire_owned()
+                .await
+                .map_err(|e| ActionRunnerError::Failure(e.to_string()))?,
+        ),
+        None => None,
+    };
+
+    let mut action = Action::new(node_index.index(), None);
+    let own_graph = graph.read().await;
+
+    // The runner is shutting down while this action was waiting to run
+    if context.is_interrupted() {
+        action.label = own_graph
+            .get_node_from_index(&node_index)
+            .map(|n| n.label());
+        action.interrupt(String::from("Aborted before running"));
+
+        return Ok(action);
+    }
+
+    if let Some(node) = own_graph.get_node_from_index(&node_index) {
+        action.label = Some(node.label());
+
+        let log_target_name = format!("{}:{}", LOG_TARGET, node_index.index());
+        let log_action_label = color::muted_light(&node.label());
+
+        trace!(
+            target: &log_target_name,
+            "Running action {}",
+            log_action_label
+        );
+
+        let worker = trace.as_ref().map(|trace| trace.acquire_worker());
+        let result = run_action(node, &mut action, &context, workspace).await;
+
+        if let (Some(trace), Some(worker)) = (&trace, worker) {
+            trace.record(&node_index, node, &action, worker);
+            trace.release_worker(worker);
+        }
+
+        result?;
+
+        if action.has_failed() {
+            trace!(
+                target: &log_target_name,
+                "Failed to run action {} in {:?}",
+                log_action_label,
+                action.duration.unwrap()
+            );
+        } else {
+            trace!(
+                target: &log_target_name,
+                "Ran action {} in {:?}",
+                log_action_label,
+                action.duration.unwrap()
+            );
+        }
+    } else {
+        action.status = ActionStatus::Invalid;
+
+        return Err(ActionRunnerError::DepGraph(DepGraphError::UnknownNode(
+            node_index.index(),
+        )));
+    }
+
+    Ok(action)
+}
+
 pub struct ActionRunner {
     bail: bool,
 
+    /// Maximum amount of actions to run in parallel.
+    concurrency: usize,
+
     pub duration: Option<Duration>,
 
+    /// The signal that interrupted the run, if any.
+    pub interrupted: Option<Signal>,
+
+    /// Record a timeline of all actions, written to `.moon/cache/runTrace.json`.
+    trace: bool,
+
     workspace: Arc<RwLock<Workspace>>,
 }
 
@@ -65,9 +206,18 @@ impl ActionRunner {
     pub fn new(workspace: Workspace) -> Self {
         debug!(target: LOG_TARGET, "Creating action runner");
 
+        let concurrency = workspace
+            .config
+            .action_runner
+            .concurrency
+            .unwrap_or_else(get_default_concurrency);
+
         ActionRunner {
             bail: false,
+            concurrency,
             duration: None,
+            interrupted: None,
+            trace: false,
             workspace: Arc::new(RwLock::new(workspace)),
         }
     }
@@ -77,6 +227,16 @@ impl ActionRunner {
         self
     }
 
+    pub fn concurrency(&mut self, value: usize) -> &mut Self {
+        self.concurrency = value.max(1);
+        self
+    }
+
+    pub fn trace(&mut self) -> &mut Self {
+        self.trace = true;
+        self
+    }
+
     pub async fn cleanup(&self) -> Result<(), ActionRunnerError> {
         let workspace = self.workspace.read().await;
 
@@ -88,17 +248,73 @@ impl ActionRunner {
         Ok(())
     }
 
+    async fn get_persistent_nodes(
+        &self,
+        graph: &DepGraph,
+    ) -> Result<HashSet<NodeIndex>, ActionRunnerError> {
+        let workspace = self.workspace.read().await;
+        let mut nodes = HashSet::new();
+
+        for index in graph.graph.node_indices() {
+            if let Some(Node::RunTarget(target_id)) = graph.get_node_from_index(&index) {
+                let (project_id, task_id) = Target::parse(target_id)?.ids()?;
+                let project = workspace.projects.load(&project_id)?;
+
+                if project.get_task(&task_id)?.options.persistent {
+                    nodes.insert(index);
+                }
+            }
+        }
+
+        Ok(nodes)
+    }
+
     pub async fn run(
         &mut self,
         graph: DepGraph,
         context: ActionContext,
     ) -> Result<Vec<Action>, ActionRunnerError> {
         let start = Instant::now();
+        let start_time = SystemTime::now();
         let node_count = graph.graph.node_count();
-        let batches = graph.sort_batched_topological()?;
-        let batches_count = batches.len();
+
+        // Also detects cycles, and is used for deterministic ordering of results
+        let order = graph.sort_topological()?;
+
+        // Track how many dependencies each node is waiting on, so that
+        // a node can be started as soon as all of its dependencies complete
+        let mut pending_deps: HashMap<NodeIndex, usize> = graph
+            .graph
+            .node_indices()
+            .map(|index| {
+                (
+                    index,
+                    graph
+                        .graph
+                        .neighbors_directed(index, Direction::Outgoing)
+                        .count(),
+                )
+            })
+            .collect();
+
+        // Persistent actions never complete, so they're deferred until
+        // all other actions have completed, and then started together
+        let persistent_nodes = self.get_persistent_nodes(&graph).await?;
+        let mut deferred_nodes = vec![];
+
+        let trace = if self.trace {
+            Some(Arc::new(RunTrace::new(
+                graph.sort_batched_topological()?,
+                start,
+            )))
+        } else {
+            None
+        };
+
         let graph = Arc::new(RwLock::new(graph));
         let context = Arc::new(context);
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let (sender, mut receiver) = mpsc::unbounded_channel();
 
         // Clean the runner state *before* running actions instead of after,
         // so that failing or broken builds can dig into and debug the state!
@@ -106,102 +322,264 @@ impl ActionRunner {
 
         debug!(
             target: LOG_TARGET,
-            "Running {} actions across {} batches", node_count, batches_count
+            "Running {} actions (concurrency of {})", node_count, self.concurrency
         );
 
-        let mut results: Vec<Action> = vec![];
+        let spawn_node = |node_index: NodeIndex| {
+            let graph_clone = Arc::clone(&graph);
+            let context_clone = Arc::clone(&context);
+            let workspace_clone = Arc::clone(&self.workspace);
+            let semaphore_clone = if persistent_nodes.contains(&node_index) {
+                None
+            } else {
+                Some(Arc::clone(&semaphore))
+            };
+            let sender_clone = sender.clone();
+            let trace_clone = trace.clone();
+
+            task::spawn(async move {
+                // Run in a nested task, so that panics are propagated as errors,
+                // instead of the runner waiting on a node that never completes
+                let result = match task::spawn(run_node(
+                    node_index,
+                    graph_clone,
+                    context_clone,
+                    workspace_clone,
+                    semaphore_clone,
+                    trace_clone,
+                ))
+                .await
+                {
+                    Ok(result) => result,
+                    Err(e) => Err(ActionRunnerError::Failure(e.to_string())),
+                };
+
+                // The receiver is only dropped when the runner has aborted
+                let _ = sender_clone.send((node_index, result));
+            });
+        };
+
+        // Start all nodes that have no dependencies
+        let mut in_flight = 0;
+
+        for index in &order {
+            if pending_deps[index] == 0 {
+                if persistent_nodes.contains(index) {
+                    deferred_nodes.push(*index);
+                } else {
+                    spawn_node(*index);
+                    in_flight += 1;
+                }
+            }
+        }
+
+        let mut results: HashMap<NodeIndex, Action> = HashMap::new();
+        let mut interrupted = None;
+        let shutdown = wait_for_shutdown_signal();
+        tokio::pin!(shutdown);
+
+        // As each node completes, start its dependents that are no longer blocked
+        while results.len() < node_count {
+            if in_flight == 0 && !deferred_nodes.is_empty() {
+                for index in deferred_nodes.drain(..) {
+                    spawn_node(index);
+                    in_flight += 1;
+                }
+            }
 
-        for (b, batch) in batches.into_iter().enumerate() {
-            let batch_count = b + 1;
-            let batch_target_name = format!("{}:batch:{}", LOG_TARGET, batch_count);
-            let actions_count = batch.len();
+            // Check for a signal first, as children that received the same
+            // signal (Ctrl+C) may exit before the runner is notified of it
+            let message = tokio::select! {
+                biased;
+
+                signal = &mut shutdown => {
+                    // Mark as interrupted immediately, so that actions completing
+                    // from this point on are aware of it
+                    context.interrupted.store(true, Ordering::SeqCst);
+                    interrupted = Some(signal);
+                    break;
+                }
+                message = receiver.recv() => message,
+            };
 
-            trace!(
-                target: &batch_target_name,
-                "Running {} actions",
-                actions_count
-            );
+            let (node_index, result) = match message {
+                Some(message) => message,
+                None => break,
+            };
 
-            let mut action_handles = vec![];
-
-            for (i, node_index) in batch.into_iter().enumerate() {
-                let action_count = i + 1;
-                let graph_clone = Arc::clone(&graph);
-                let context_clone = Arc::clone(&context);
-                let workspace_clone = Arc::clone(&self.workspace);
-
-                action_handles.push(task::spawn(async move {
-                    let mut action = Action::new(node_index.index(), None);
-                    let own_graph = graph_clone.read().await;
-
-                    if let Some(node) = own_graph.get_node_from_index(&node_index) {
-                        action.label = Some(node.label());
-
-                        let log_target_name =
-                            format!("{}:batch:{}:{}", LOG_TARGET, batch_count, action_count);
-                        let log_action_label = color::muted_light(&node.label());
-
-                        trace!(
-                            target: &log_target_name,
-                            "Running action {}",
-                            log_action_label
-                        );
-
-                        run_action(node, &mut action, &context_clone, workspace_clone).await?;
-
-                        if action.has_failed() {
-                            trace!(
-                                target: &log_target_name,
-                                "Failed to run action {} in {:?}",
-                                log_action_label,
-                                action.duration.unwrap()
-                            );
-                        } else {
-                            trace!(
-                                target: &log_target_name,
-                                "Ran action {} in {:?}",
-                                log_action_label,
-                                action.duration.unwrap()
-                            );
-                        }
-                    } else {
-                        action.status = ActionStatus::Invalid;
+            in_flight -= 1;
+
+            let action = result?;
+
+            if action.should_abort() {
+                error!(
+                    target: LOG_TARGET,
+                    "Encountered a critical error, aborting the action runner"
+                );
+            }
+
+            if self.bail && action.error.is_some() || action.should_abort() {
+                let error = action.error.clone().unwrap();
+
+                // Still report the actions that ran before bailing
+                results.insert(node_index, action);
+
+                self.duration = Some(start.elapsed());
+
+                let results = order
+                    .iter()
+                    .filter_map(|index| results.remove(index))
+                    .collect::<Vec<_>>();
 
-                        return Err(ActionRunnerError::DepGraph(DepGraphError::UnknownNode(
-                            node_index.index(),
-                        )));
+                self.create_run_report(&results, &context, start_time).await;
+                self.create_run_trace(trace.as_deref()).await;
+
+                return Err(ActionRunnerError::Failure(error));
+            }
+
+            let failed_label = if action.has_failed() {
+                action.label.clone()
+            } else {
+                None
+            };
+
+            results.insert(node_index, action);
+
+            let own_graph = graph.read().await;
+
+            // Dependents of a failed action can not succeed, so mark them (and their own
+            // dependents) as skipped, while unrelated actions continue to run
+            if let Some(failed_label) = failed_label {
+                let mut queue = VecDeque::from(own_graph.get_dependents_of_index(&node_index));
+
+                while let Some(index) = queue.pop_front() {
+                    if results.contains_key(&index) {
+                        continue;
                     }
 
-                    Ok(action)
-                }));
+                    let mut action = Action::new(
+                        index.index(),
+                        own_graph.get_node_from_index(&index).map(|n| n.label()),
+                    );
+
+                    action.skip(format!("Dependency {} failed", failed_label));
+
+                    debug!(
+                        target: LOG_TARGET,
+                        "Skipping action {}, as its dependency {} failed",
+                        color::muted_light(action.label.as_ref().unwrap()),
+                        color::muted_light(&failed_label),
+                    );
+
+                    results.insert(index, action);
+                    queue.extend(own_graph.get_dependents_of_index(&index));
+                }
+
+                continue;
             }
 
-            // Wait for all actions in this batch to complete,
-            // while also handling and propagating errors
-            for handle in action_handles {
-                match handle.await {
-                    Ok(Ok(result)) => {
-                        if result.should_abort() {
-                            error!(
-                                target: &batch_target_name,
-                                "Encountered a critical error, aborting the action runner"
-                            );
-                        }
+            for dependent_index in own_graph.get_dependents_of_index(&node_index) {
+                let count = pending_deps.get_mut(&dependent_index).unwrap();
+                *count -= 1;
 
-                        if self.bail && result.error.is_some() || result.should_abort() {
-                            return Err(ActionRunnerError::Failure(result.error.unwrap()));
-                        }
+                // May have already been skipped by another failed dependency
+                if *count == 0 && !results.contains_key(&dependent_index) {
+                    if persistent_nodes.contains(&dependent_index) {
+                        deferred_nodes.push(dependent_index);
+                    } else {
+                        spawn_node(dependent_index);
+                        in_flight += 1;
+                    }
+                }
+            }
+        }
 
-                        results.push(result);
+        self.interrupted = interrupted;
+
+        if let Some(signal) = interrupted {
+            warn!(
+                target: LOG_TARGET,
+                "Received {} signal, cancelling {} in-flight actions", signal, in_flight
+            );
+
+            // Give running processes a chance to exit gracefully,
+            // and kill them if they have not done so in time
+            signal_running_processes(signal);
+
+            let deadline = time::sleep(SHUTDOWN_GRACE_PERIOD);
+            tokio::pin!(deadline);
+
+            let mut killed = false;
+
+            while in_flight > 0 {
+                tokio::select! {
+                    message = receiver.recv() => {
+                        let (node_index, result) = match message {
+                            Some(message) => message,
+                            None => break,
+                        };
+
+                        in_flight -= 1;
+
+                        // Actions that completed during the grace period are reported as-is,
+                        // while unfinished actions and failures are reported as aborted
+                        match result {
+                            Ok(mut action) => {
+                                // Never finished, so was cut short by the signal
+                                if matches!(action.status, ActionStatus::Running) {
+                                    action.interrupt(format!("Aborted by {} signal", signal));
+                                }
+
+                                results.insert(node_index, action);
+                            }
+                            Err(error) => {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    "Action failed while shutting down: {}", error
+                                );
+                            }
+                        }
                     }
-                    Ok(Err(e)) => {
-                        return Err(e);
+                    _ = &mut deadline => {
+                        // Orphaned grandchildren may still be holding onto the output
+                        // pipes of killed processes, so stop waiting on them
+                        if killed {
+                            break;
+                        }
+
+                        killed = true;
+                        signal_running_processes(Signal::Kill);
+                        deadline.as_mut().reset(time::Instant::now() + SHUTDOWN_KILL_PERIOD);
                     }
-                    Err(e) => {
-                        return Err(ActionRunnerError::Failure(e.to_string()));
+                    // Pressing Ctrl+C again skips the grace period
+                    _ = signal::ctrl_c(), if !killed => {
+                        killed = true;
+                        signal_running_processes(Signal::Kill);
+                        deadline.as_mut().reset(time::Instant::now() + SHUTDOWN_KILL_PERIOD);
                     }
                 }
             }
+
+            // In-flight actions were interrupted, and the rest never ran
+            let own_graph = graph.read().await;
+
+            for index in &order {
+                if results.contains_key(index) {
+                    continue;
+                }
+
+                let mut action = Action::new(
+                    index.index(),
+                    own_graph.get_node_from_index(index).map(|n| n.label()),
+                );
+
+                action.interrupt(format!("Aborted by {} signal", signal));
+
+                results.insert(*index, action);
+            }
+
+            // Runfiles may have been partially written while interrupted
+            self.cleanup().await?;
         }
 
         self.duration = Some(start.elapsed());
@@ -213,6 +591,46 @@ impl ActionRunner {
             self.duration.unwrap()
         );
 
+        // Return results in topological order, regardless of completion order
+        let results = order
+            .into_iter()
+            .filter_map(|index| results.remove(&index))
+            .collect::<Vec<_>>();
+
+        self.create_run_report(&results, &context, start_time).await;
+        self.create_run_trace(trace.as_deref()).await;
+
         Ok(results)
     }
+
+    async fn create_run_report(
+        &self,
+        results: &[Action],
+        context: &ActionContext,
+        start_time: SystemTime,
+    ) {
+        let workspace = self.workspace.read().await;
+        let report = RunReport::new(
+            results,
+            context,
+            &workspace.root,
+            start_time,
+            self.duration.unwrap_or_default(),
+            self.interrupted.map(|signal| signal.to_string()),
+        );
+
+        if let Err(error) = workspace.cache.create_run_report(&report).await {
+            warn!(target: LOG_TARGET, "Failed to write run report: {}", error);
+        }
+    }
+
+    async fn create_run_trace(&self, trace: Option<&RunTrace>) {
+        if let Some(trace) = trace {
+            let workspace = self.workspace.read().await;
+
+            if let Err(error) = workspace.cache.create_run_trace(&trace.to_file()).await {
+                warn!(target: LOG_TARGET, "Failed to write run trace: {}", error);
+            }
+        }
+    }
 }
@@ -0,0 +1,223 @@
+use crate::dep_graph::{BatchedTopoSort, NodeIndex};
+use crate::node::Node;
+use moon_action::{Action, ActionStatus};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEventArgs {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ActionStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worker: Option<usize>,
+}
+
+/// An event in the Chrome Trace Event format. Timestamps and durations
+/// are in microseconds, relative from the start of the run.
+#[derive(Serialize)]
+pub struct TraceEvent {
+    pub args: TraceEventArgs,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cat: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dur: Option<u128>,
+
+    pub name: String,
+
+    pub ph: String,
+
+    pub pid: u32,
+
+    pub tid: usize,
+
+    pub ts: u128,
+}
+
+/// A timeline of a pipeline run, written to `.moon/cache/runTrace.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunTraceFile {
+    pub display_time_unit: String,
+
+    pub trace_events: Vec<TraceEvent>,
+}
+
+#[derive(Default)]
+struct Workers {
+    available: BTreeSet<usize>,
+    count: usize,
+}
+
+/// Records when each action started and finished, the batch it belongs to
+/// in the dependency graph, and the worker slot it ran in.
+pub struct RunTrace {
+    batches: HashMap<NodeIndex, usize>,
+
+    events: Mutex<Vec<TraceEvent>>,
+
+    pid: u32,
+
+    start: Instant,
+
+    workers: Mutex<Workers>,
+}
+
+impl RunTrace {
+    pub fn new(batches: BatchedTopoSort, start: Instant) -> Self {
+        let mut indexes = HashMap::new();
+
+        for (batch, nodes) in batches.into_iter().enumerate() {
+            for node in nodes {
+                indexes.insert(node, batch);
+            }
+        }
+
+        RunTrace {
+            batches: indexes,
+            events: Mutex::new(vec![]),
+            pid: std::process::id(),
+            start,
+            workers: Mutex::new(Workers::default()),
+        }
+    }
+
+    /// Reserve the lowest available worker slot. Slots are only created when all
+    /// others are in use, so their count reflects the actual parallelism of the run.
+    pub fn acquire_worker(&self) -> usize {
+        let mut workers = self.workers.lock().unwrap();
+
+        match workers.available.iter().next().copied() {
+            Some(worker) => {
+                workers.available.remove(&worker);
+                worker
+            }
+            None => {
+                workers.count += 1;
+                workers.count
+            }
+        }
+    }
+
+    pub fn release_worker(&self, worker: usize) {
+        self.workers.lock().unwrap().available.insert(worker);
+    }
+
+    pub fn record(&self, node_index: &NodeIndex, node: &Node, action: &Action, worker: usize) {
+        let batch = self.batches.get(node_index).copied();
+        let mut events = vec![TraceEvent {
+            args: TraceEventArgs {
+                batch,
+                name: None,
+                status: Some(action.status.clone()),
+                worker: Some(worker),
+            },
+            cat: Some(get_category(node)),
+            dur: Some(action.duration.unwrap_or_default().as_micros()),
+            name: node.label(),
+            ph: "X".into(),
+            pid: self.pid,
+            tid: worker,
+            ts: self.get_timestamp(action.start_time),
+        }];
+
+        // Nest each attempt of a target within the action
+        if let Some(attempts) = &action.attempts {
+            for attempt in attempts {
+                events.push(TraceEvent {
+                    args: TraceEventArgs {
+                        batch,
+                        name: None,
+                        status: None,
+                        worker: Some(worker),
+                    },
+                    cat: Some("Attempt".into()),
+                    dur: Some(attempt.duration.unwrap_or_default().as_micros()),
+                    name: format!("Attempt {}", attempt.index),
+                    ph: "X".into(),
+                    pid: self.pid,
+                    tid: worker,
+                    ts: self.get_timestamp(attempt.start_time),
+                });
+            }
+        }
+
+        self.events.lock().unwrap().extend(events);
+    }
+
+    pub fn to_file(&self) -> RunTraceFile {
+        let mut trace_events = vec![TraceEvent {
+            args: TraceEventArgs {
+                batch: None,
+                name: Some("moon".into()),
+                status: None,
+                worker: None,
+            },
+            cat: None,
+            dur: None,
+            name: "process_name".into(),
+            ph: "M".into(),
+            pid: self.pid,
+            tid: 0,
+            ts: 0,
+        }];
+
+        // Label each thread in the viewer by its worker slot
+        for worker in 1..=self.workers.lock().unwrap().count {
+            trace_events.push(TraceEvent {
+                args: TraceEventArgs {
+                    batch: None,
+                    name: Some(format!("Worker {}", worker)),
+                    status: None,
+                    worker: None,
+                },
+                cat: None,
+                dur: None,
+                name: "thread_name".into(),
+                ph: "M".into(),
+                pid: self.pid,
+                tid: worker,
+                ts: 0,
+            });
+        }
+
+        let mut events = self.events.lock().unwrap().drain(..).collect::<Vec<_>>();
+
+        events.sort_by(|a, b| a.ts.cmp(&b.ts).then(a.tid.cmp(&b.tid)));
+
+        trace_events.extend(events);
+
+        RunTraceFile {
+            display_time_unit: "ms".into(),
+            trace_events,
+        }
+    }
+
+    fn get_timestamp(&self, instant: Instant) -> u128 {
+        instant
+            .checked_duration_since(self.start)
+            .unwrap_or(Duration::ZERO)
+            .as_micros()
+    }
+}
+
+fn get_category(node: &Node) -> String {
+    match node {
+        Node::InstallDeps(_) => "InstallDeps",
+        Node::RunTarget(_) => "RunTarget",
+        Node::SetupToolchain => "SetupToolchain",
+        Node::SyncProject(_, _) => "SyncProject",
+    }
+    .into()
+}
@@ -1,5 +1,5 @@
 use insta::assert_snapshot;
-use moon_action_runner::{BatchedTopoSort, DepGraph, NodeIndex};
+use moon_action_runner::{BatchedTopoSort, DepGraph, Node, NodeIndex};
 use moon_cache::CacheEngine;
 use moon_config::{GlobalProjectConfig, WorkspaceConfig};
 use moon_project::{ProjectGraph, Target};
@@ -49,6 +49,7 @@ async fn create_tasks_project_graph() -> ProjectGraph {
             ("mergePrepend".to_owned(), "merge-prepend".to_owned()),
             ("mergeReplace".to_owned(), "merge-replace".to_owned()),
             ("no-tasks".to_owned(), "no-tasks".to_owned()),
+            ("persistent".to_owned(), "persistent".to_owned()),
         ]),
         ..WorkspaceConfig::default()
     };
@@ -313,6 +314,21 @@ mod run_target {
         assert_snapshot!(graph.to_dot());
     }
 
+    #[tokio::test]
+    #[should_panic(expected = "PersistentDependency(\"persistent:consumer\", \"persistent:dev\")")]
+    async fn errors_for_persistent_dependency() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        graph
+            .run_target(
+                &Target::new("persistent", "consumer").unwrap(),
+                &projects,
+                None,
+            )
+            .unwrap();
+    }
+
     #[tokio::test]
     #[should_panic(expected = "Project(UnconfiguredTask(\"build\", \"tasks\"))")]
     async fn errors_for_unknown_task() {
@@ -327,6 +343,118 @@ mod run_target {
     }
 }
 
+mod run_target_from_project {
+    use super::*;
+
+    #[tokio::test]
+    async fn runs_deps_of_project_for_target_deps_scope() {
+        let projects = create_tasks_project_graph().await;
+        let project = projects.load("build-a").unwrap();
+
+        let mut graph = DepGraph::default();
+        let mut targets = graph
+            .run_target_from_project(
+                &Target::parse("^:build").unwrap(),
+                Some(&project),
+                &projects,
+                None,
+            )
+            .unwrap();
+        targets.sort();
+
+        assert_eq!(targets, vec!["basic:build", "build-c:build"]);
+        assert!(graph
+            .get_index_from_node(&Node::RunTarget("build-a:build".to_owned()))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn runs_project_for_target_self_scope() {
+        let projects = create_tasks_project_graph().await;
+        let project = projects.load("basic").unwrap();
+
+        let mut graph = DepGraph::default();
+        let targets = graph
+            .run_target_from_project(
+                &Target::parse("~:lint").unwrap(),
+                Some(&project),
+                &projects,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(targets, vec!["basic:lint"]);
+    }
+
+    #[tokio::test]
+    async fn returns_all_inserted_targets() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        let targets = graph
+            .run_target_from_project(&Target::parse(":build").unwrap(), None, &projects, None)
+            .unwrap();
+
+        assert_eq!(
+            targets,
+            vec![
+                "basic:build",
+                "build-a:build",
+                "build-b:build",
+                "build-c:build"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn runs_tagged_projects_for_target_tag_scope() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        let targets = graph
+            .run_target_from_project(
+                &Target::parse("#frontend:build").unwrap(),
+                None,
+                &projects,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(targets, vec!["build-b:build", "build-c:build"]);
+        assert!(graph
+            .get_index_from_node(&Node::RunTarget("build-a:build".to_owned()))
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn runs_nothing_for_unknown_tag() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        let targets = graph
+            .run_target_from_project(
+                &Target::parse("#unknown:build").unwrap(),
+                None,
+                &projects,
+                None,
+            )
+            .unwrap();
+
+        assert!(targets.is_empty());
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Project(Target(NoProjectSelfInRunContext))")]
+    async fn errors_for_target_self_scope_without_project() {
+        let projects = create_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        graph
+            .run_target_from_project(&Target::parse("~:lint").unwrap(), None, &projects, None)
+            .unwrap();
+    }
+}
+
 mod run_target_if_touched {
     use super::*;
 
@@ -512,3 +640,101 @@ mod sync_project {
         assert_snapshot!(graph.to_dot());
     }
 }
+
+mod get_dependents_of_index {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_direct_dependents() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        graph
+            .run_target(&Target::new("basic", "test").unwrap(), &projects, None)
+            .unwrap();
+        graph
+            .run_target(&Target::new("basic", "lint").unwrap(), &projects, None)
+            .unwrap();
+        graph
+            .run_target(&Target::new("chain", "a").unwrap(), &projects, None)
+            .unwrap();
+
+        // sync project -> test, lint
+        assert_eq!(
+            graph.get_dependents_of_index(&NodeIndex::new(2)),
+            vec![NodeIndex::new(3), NodeIndex::new(4)]
+        );
+
+        // f -> e
+        assert_eq!(
+            graph.get_dependents_of_index(&NodeIndex::new(11)),
+            vec![NodeIndex::new(10)]
+        );
+
+        // a
+        assert_eq!(graph.get_dependents_of_index(&NodeIndex::new(6)), vec![]);
+    }
+}
+
+mod to_json {
+    use super::*;
+
+    #[tokio::test]
+    async fn includes_metadata_and_typed_edges() {
+        let projects = create_tasks_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        graph
+            .run_target(&Target::new("chain", "a").unwrap(), &projects, None)
+            .unwrap();
+
+        let json = serde_json::to_value(graph.to_json(&projects).unwrap()).unwrap();
+
+        assert_eq!(json["version"], 1);
+
+        let nodes = json["nodes"].as_array().unwrap();
+        let sync = nodes
+            .iter()
+            .find(|node| node["label"] == "SyncNodeProject(chain)")
+            .unwrap();
+        let run = nodes
+            .iter()
+            .find(|node| node["label"] == "RunTarget(chain:a)")
+            .unwrap();
+
+        assert_eq!(sync["type"], "syncProject");
+        assert_eq!(sync["language"], "node");
+        assert_eq!(sync["project"]["id"], "chain");
+        assert_eq!(run["type"], "runTarget");
+        assert_eq!(run["target"], "chain:a");
+        assert_eq!(run["task"]["target"], "chain:a");
+        assert!(run["project"].is_null());
+
+        let edge_types = json["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|edge| edge["source"] == run["id"])
+            .map(|edge| edge["type"].as_str().unwrap())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            edge_types,
+            HashSet::from(["installDeps", "syncProject", "taskDependency"])
+        );
+    }
+}
+
+mod to_mermaid {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_graph() {
+        let projects = create_project_graph().await;
+
+        let mut graph = DepGraph::default();
+        graph.sync_project("foo", &projects).unwrap();
+
+        assert_snapshot!(graph.to_mermaid());
+    }
+}
@@ -1,3 +1,5 @@
+use serde::Serialize;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 pub struct Attempt {
@@ -5,6 +7,9 @@ pub struct Attempt {
 
     pub index: u8,
 
+    /// Log file of the attempt's output, within the target's runs directory.
+    pub log_path: Option<PathBuf>,
+
     pub start_time: Instant,
 }
 
@@ -13,6 +18,7 @@ impl Attempt {
         Attempt {
             duration: None,
             index,
+            log_path: None,
             start_time: Instant::now(),
         }
     }
@@ -22,7 +28,10 @@ impl Attempt {
     }
 }
 
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub enum ActionStatus {
+    Aborted, // When interrupted by a signal
     Cached,
     // CachedFromRemote, // TODO
     Failed,
@@ -31,19 +40,29 @@ pub enum ActionStatus {
     Passed,
     Running,
     Skipped, // When nothing happened
+    TimedOut,
 }
 
 pub struct Action {
     pub attempts: Option<Vec<Attempt>>,
 
+    /// When the target was not cached, the reasons why the hash changed.
+    pub cache_miss_reasons: Option<Vec<String>>,
+
     pub duration: Option<Duration>,
 
     pub error: Option<String>,
 
+    /// When a target, the hash that was generated for the run.
+    pub hash: Option<String>,
+
     pub label: Option<String>,
 
     pub node_index: usize,
 
+    /// When skipped because a dependency failed, the reason why.
+    pub skip_reason: Option<String>,
+
     pub start_time: Instant,
 
     pub status: ActionStatus,
@@ -53,10 +72,13 @@ impl Action {
     pub fn new(node_index: usize, label: Option<String>) -> Self {
         Action {
             attempts: None,
+            cache_miss_reasons: None,
             duration: None,
             error: None,
+            hash: None,
             label,
             node_index,
+            skip_reason: None,
             start_time: Instant::now(),
             status: ActionStatus::Running,
         }
@@ -75,6 +97,19 @@ impl Action {
     pub fn has_failed(&self) -> bool {
         matches!(self.status, ActionStatus::Failed)
             || matches!(self.status, ActionStatus::FailedAndAbort)
+            || matches!(self.status, ActionStatus::TimedOut)
+            || matches!(self.status, ActionStatus::Aborted)
+    }
+
+    pub fn interrupt(&mut self, reason: String) {
+        self.error = Some(reason);
+        self.status = ActionStatus::Aborted;
+        self.duration = Some(self.start_time.elapsed());
+    }
+
+    pub fn time_out(&mut self, error: String) {
+        self.fail(error);
+        self.status = ActionStatus::TimedOut;
     }
 
     pub fn pass(&mut self, status: ActionStatus) {
@@ -82,6 +117,12 @@ impl Action {
         self.duration = Some(self.start_time.elapsed());
     }
 
+    pub fn skip(&mut self, reason: String) {
+        self.skip_reason = Some(reason);
+        self.status = ActionStatus::Skipped;
+        self.duration = Some(Duration::new(0, 0));
+    }
+
     pub fn should_abort(&self) -> bool {
         matches!(self.status, ActionStatus::FailedAndAbort)
     }
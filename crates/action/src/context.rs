@@ -1,6 +1,8 @@
 use clap::ValueEnum;
 use moon_project::TouchedFilePaths;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 #[derive(ValueEnum, Clone, Debug)]
 pub enum ProfileType {
@@ -10,6 +12,9 @@ pub enum ProfileType {
 
 #[derive(Default)]
 pub struct ActionContext {
+    /// Set by the action runner when a shutdown signal has been received.
+    pub interrupted: Arc<AtomicBool>,
+
     pub passthrough_args: Vec<String>,
 
     pub primary_targets: HashSet<String>,
@@ -18,3 +23,9 @@ pub struct ActionContext {
 
     pub touched_files: TouchedFilePaths,
 }
+
+impl ActionContext {
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+}
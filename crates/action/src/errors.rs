@@ -7,6 +7,9 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ActionError {
+    #[error("Target <target>{0}</target> was interrupted.")]
+    Interrupted(String),
+
     #[error(transparent)]
     Moon(#[from] MoonError),
 
@@ -4,20 +4,29 @@ use crate::errors::ActionError;
 use crate::target::{
     create_node_target_command, create_system_target_command, create_target_hasher,
 };
-use moon_cache::RunTargetState;
+use moon_cache::{is_readable, RunTargetState};
 use moon_config::TaskType;
+use moon_error::MoonError;
+use moon_hasher::TargetHasher;
 use moon_logger::{color, debug, warn};
 use moon_project::{Project, Target, Task};
 use moon_terminal::output::{label_checkpoint, Checkpoint};
 use moon_utils::process::{join_args, output_to_string, Command, Output};
-use moon_utils::{is_ci, is_test_env, path, time};
+use moon_utils::time::chrono::{self, Local};
+use moon_utils::{fs, is_ci, is_test_env, path, string_vec, time};
 use moon_workspace::Workspace;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 const LOG_TARGET: &str = "moon:action:run-target";
 
+// When a captured process fails, only print the tail of its output,
+// as the full output is always available in the attempt's log file.
+const MAX_FAILED_OUTPUT_LINES: usize = 100;
+
 async fn create_env_vars(
     workspace: &Workspace,
     project: &Project,
@@ -95,59 +104,77 @@ async fn create_target_command(
     Ok(command)
 }
 
-pub async fn run_target(
-    action: &mut Action,
-    context: &ActionContext,
-    workspace: Arc<RwLock<Workspace>>,
-    target_id: &str,
-) -> Result<ActionStatus, ActionError> {
-    debug!(
-        target: LOG_TARGET,
-        "Running target {}",
-        color::id(target_id)
-    );
-
-    let workspace = workspace.read().await;
-    let mut cache = workspace.cache.cache_run_target_state(target_id).await?;
-
-    // Gather the project and task
-    let is_primary = context.primary_targets.contains(target_id);
-    let (project_id, task_id) = Target::parse(target_id)?.ids()?;
-    let project = workspace.projects.load(&project_id)?;
-    let task = project.get_task(&task_id)?;
-
-    // Abort early if this build has already been cached/hashed
-    let hasher =
-        create_target_hasher(&workspace, &project, task, &context.passthrough_args).await?;
-    let hash = hasher.to_hash();
-
-    debug!(
-        target: LOG_TARGET,
-        "Generated hash {} for target {}",
-        color::symbol(&hash),
-        color::id(target_id)
-    );
-
-    if cache.item.hash == hash {
+/// Restore the task's outputs from the hash's archive on a cache hit.
+/// If the outputs were never archived, or the archive has since been removed,
+/// nothing is restored and false is returned, so that the target is ran again.
+async fn hydrate_outputs_from_archive(
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+    hash: &str,
+) -> Result<bool, ActionError> {
+    let hydrated = workspace
+        .cache
+        .hydrate_task_outputs(hash, &project.root, &get_output_paths(task))
+        .await?;
+
+    if !hydrated {
         debug!(
             target: LOG_TARGET,
-            "Hash exists for {}, aborting run",
-            color::id(target_id),
+            "Outputs for {} do not exist in the cache",
+            color::id(&task.target),
         );
+    }
 
-        println!(
-            "{} {}",
-            label_checkpoint(target_id, Checkpoint::Pass),
-            color::muted("(cached)")
-        );
+    Ok(hydrated)
+}
 
-        print_cache_item(&cache.item);
+fn get_output_paths(task: &Task) -> Vec<PathBuf> {
+    task.output_paths.iter().cloned().collect()
+}
 
-        return Ok(ActionStatus::Cached);
+/// Restore the task's outputs from the remote cache, if one has been configured. Failures
+/// are logged and treated as a cache miss, as the remote cache should never fail a run.
+async fn hydrate_outputs_from_remote(
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+    hash: &str,
+) -> bool {
+    match workspace
+        .cache
+        .hydrate_task_outputs_from_remote(hash, &project.root, &get_output_paths(task))
+        .await
+    {
+        Ok(hydrated) => hydrated,
+        Err(error) => {
+            warn!(
+                target: LOG_TARGET,
+                "Failed to download hash {} for {} from the remote cache: {}",
+                color::symbol(hash),
+                color::id(&task.target),
+                error
+            );
+
+            false
+        }
     }
+}
 
+/// Run the task's command as a child process and capture its output.
+/// If the process fails and `retry_count` is greater than 0,
+/// attempt the process again in case it passes. Attempts are tracked
+/// on the action, regardless of whether the process passed or failed.
+async fn run_target_command(
+    action: &mut Action,
+    context: &ActionContext,
+    workspace: &Workspace,
+    project: &Project,
+    task: &Task,
+    target_id: &str,
+) -> Result<Output, ActionError> {
     // Build the command to run based on the task
-    let mut command = create_target_command(context, &workspace, &project, task).await?;
+    let mut command = create_target_command(context, workspace, project, task).await?;
 
     if !context.passthrough_args.is_empty() {
         command.args(&context.passthrough_args);
@@ -161,15 +188,37 @@ pub async fn run_target(
         command.inherit_colors();
     }
 
-    // Run the command as a child process and capture its output.
-    // If the process fails and `retry_count` is greater than 0,
-    // attempt the process again in case it passes.
+    if let Some(timeout) = task.options.timeout {
+        command.timeout(Duration::from_secs(timeout));
+    }
+
+    // Logs of a previous run are stale, and may have more attempts than this run
+    if let Err(error) = workspace.cache.delete_target_logs(target_id).await {
+        warn!(
+            target: LOG_TARGET,
+            "Failed to delete previous logs for {}: {}",
+            color::id(target_id),
+            error
+        );
+    }
+
     let attempt_total = task.options.retry_count + 1;
     let mut attempt_index = 1;
-    let mut attempts = vec![];
+    let attempts = action.attempts.get_or_insert_with(Vec::new);
+    let is_primary = context.primary_targets.contains(target_id);
+    let is_persistent = task.options.persistent;
     let is_real_ci = is_ci() && !is_test_env();
-    let stream_output = is_primary || is_real_ci;
-    let output;
+    let stream_output = is_primary || is_persistent || is_real_ci;
+
+    // Label each line when multiple processes may be streaming side by side
+    let prefix_output =
+        is_real_ci || (is_persistent && (!is_primary || context.primary_targets.len() > 1));
+
+    // Processes that are not attached to an interactive terminal run in their own
+    // process group, so that their entire tree can be signaled when interrupted
+    if !stream_output || prefix_output {
+        command.new_process_group();
+    }
 
     loop {
         let mut attempt = Attempt::new(attempt_index);
@@ -178,16 +227,16 @@ pub async fn run_target(
             // Print label *before* output is streamed since it may stay open forever,
             // or it may use ANSI escape codes to alter the terminal.
             print_target_label(target_id, &attempt, attempt_total, Checkpoint::Pass);
-            print_target_command(&workspace, &project, task, &context.passthrough_args);
+            print_target_command(workspace, project, task, &context.passthrough_args);
 
             // If this target matches the primary target (the last task to run),
             // then we want to stream the output directly to the parent (inherit mode).
             command
-                .exec_stream_and_capture_output(if is_real_ci { Some(target_id) } else { None })
+                .exec_stream_and_capture_output(if prefix_output { Some(target_id) } else { None })
                 .await
         } else {
             print_target_label(target_id, &attempt, attempt_total, Checkpoint::Start);
-            print_target_command(&workspace, &project, task, &context.passthrough_args);
+            print_target_command(workspace, project, task, &context.passthrough_args);
 
             // Otherwise we run the process in the background and write the output
             // once it has completed.
@@ -196,6 +245,50 @@ pub async fn run_target(
 
         attempt.done();
 
+        // The runner is shutting down, so the process was most likely signaled,
+        // and its output can not be trusted. Never retry or cache it!
+        if context.is_interrupted() {
+            return Err(ActionError::Interrupted(target_id.to_owned()));
+        }
+
+        let log = match &possible_output {
+            Ok(out) => Some(create_attempt_log(
+                target_id,
+                &attempt,
+                attempt_total,
+                &format!("exited with code {}", out.status.code().unwrap_or(-1)),
+                Some(out),
+            )),
+            Err(MoonError::ProcessTimeout(_, timeout)) => Some(create_attempt_log(
+                target_id,
+                &attempt,
+                attempt_total,
+                &format!("timed out after {} seconds", timeout),
+                None,
+            )),
+            Err(_) => None,
+        };
+
+        if let Some(log) = log {
+            match workspace
+                .cache
+                .create_target_log(target_id, attempt.index, &log)
+                .await
+            {
+                Ok(log_path) => {
+                    attempt.log_path = Some(log_path);
+                }
+                Err(error) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Failed to write log for {}: {}",
+                        color::id(target_id),
+                        error
+                    );
+                }
+            };
+        }
+
         match possible_output {
             // zero and non-zero exit codes
             Ok(out) => {
@@ -208,8 +301,7 @@ pub async fn run_target(
                 attempts.push(attempt);
 
                 if out.status.success() {
-                    output = out;
-                    break;
+                    return Ok(out);
                 } else if attempt_index >= attempt_total {
                     return Err(ActionError::Moon(command.output_to_error(&out, false)));
                 } else {
@@ -223,28 +315,256 @@ pub async fn run_target(
                     );
                 }
             }
+            // process took too long and was terminated,
+            // so treat it like a failure and possibly retry
+            Err(MoonError::ProcessTimeout(bin, timeout)) => {
+                print_target_label(target_id, &attempt, attempt_total, Checkpoint::Fail);
+
+                attempts.push(attempt);
+
+                if attempt_index >= attempt_total {
+                    return Err(ActionError::Moon(MoonError::ProcessTimeout(bin, timeout)));
+                } else {
+                    attempt_index += 1;
+
+                    warn!(
+                        target: LOG_TARGET,
+                        "Target {} timed out after {} seconds, running again with attempt {}",
+                        color::target(target_id),
+                        timeout,
+                        attempt_index
+                    );
+                }
+            }
             // process itself failed
             Err(error) => {
                 return Err(ActionError::Moon(error));
             }
         }
     }
+}
+
+/// Create the contents of an attempt's log file, which includes metadata
+/// about the attempt (timestamps, status), followed by its output.
+fn create_attempt_log(
+    target_id: &str,
+    attempt: &Attempt,
+    attempt_total: u8,
+    status: &str,
+    output: Option<&Output>,
+) -> String {
+    let duration = attempt.duration.unwrap_or_default();
+    let finished = Local::now();
+    let started = finished
+        - chrono::Duration::from_std(duration).unwrap_or_else(|_| chrono::Duration::zero());
+
+    let mut log = vec![
+        format!("Target: {}", target_id),
+        format!("Attempt: {}/{}", attempt.index, attempt_total),
+        format!("Started: {}", started.to_rfc3339()),
+        format!("Finished: {}", finished.to_rfc3339()),
+        format!("Duration: {}", time::elapsed(duration)),
+        format!("Status: {}", status),
+    ];
+
+    if let Some(output) = output {
+        log.push(String::new());
+        log.push(String::from("==== stderr ===="));
+        log.push(output_to_string(&output.stderr));
+        log.push(String::from("==== stdout ===="));
+        log.push(output_to_string(&output.stdout));
+    }
+
+    log.join("\n")
+}
+
+/// Compare the current hash against the hash of the previous run, and return
+/// a list of reasons for why the target could not be hydrated from the cache.
+async fn get_cache_miss_reasons(
+    workspace: &Workspace,
+    hasher: &TargetHasher,
+    hash: &str,
+    previous_hash: &str,
+) -> Vec<String> {
+    if !is_readable() {
+        return string_vec!["cache is disabled"];
+    }
+
+    if previous_hash.is_empty() {
+        return string_vec!["no previous run"];
+    }
+
+    if previous_hash == hash {
+        return string_vec!["outputs missing from the cache"];
+    }
+
+    let previous_path = workspace.cache.get_hash_path(previous_hash);
+
+    if !previous_path.exists() {
+        return string_vec!["previous hash manifest missing"];
+    }
+
+    match fs::read_json::<_, TargetHasher>(&previous_path).await {
+        Ok(previous_hasher) => {
+            let reasons = hasher.diff(&previous_hasher);
+
+            if reasons.is_empty() {
+                string_vec!["hash changed"]
+            } else {
+                reasons
+            }
+        }
+        Err(_) => string_vec!["previous hash manifest invalid"],
+    }
+}
+
+pub async fn run_target(
+    action: &mut Action,
+    context: &ActionContext,
+    workspace: Arc<RwLock<Workspace>>,
+    target_id: &str,
+) -> Result<ActionStatus, ActionError> {
+    debug!(
+        target: LOG_TARGET,
+        "Running target {}",
+        color::id(target_id)
+    );
 
-    // Hard link outputs to the `.moon/cache/out` folder and to the cloud,
-    // so that subsequent builds are faster, and any local outputs
-    // can be rehydrated easily.
-    for output_path in &task.output_paths {
-        workspace
-            .cache
-            .link_task_output_to_out(&hash, &project.root, output_path)
-            .await?;
+    let workspace = workspace.read().await;
+
+    // Gather the project and task
+    let (project_id, task_id) = Target::parse(target_id)?.ids()?;
+    let project = workspace.projects.load(&project_id)?;
+    let task = project.get_task(&task_id)?;
+
+    // Persistent tasks never complete, so they are never hashed or cached
+    if task.options.persistent {
+        run_target_command(action, context, &workspace, &project, task, target_id).await?;
+
+        return Ok(ActionStatus::Passed);
     }
 
-    // Save the new hash
+    let mut cache = workspace.cache.cache_run_target_state(target_id).await?;
+
+    // Abort early if this build has already been cached/hashed
+    let hasher =
+        create_target_hasher(&workspace, &project, task, &context.passthrough_args).await?;
+    let hash = hasher.to_hash();
+
+    action.hash = Some(hash.clone());
+
+    debug!(
+        target: LOG_TARGET,
+        "Generated hash {} for target {}",
+        color::symbol(&hash),
+        color::id(target_id)
+    );
+
+    // The run state is never loaded when the cache is off, but be explicit,
+    // as a stale hash must never be treated as a hit in this mode
+    if is_readable() && cache.item.hash == hash {
+        if hydrate_outputs_from_archive(&workspace, &project, task, &hash).await? {
+            debug!(
+                target: LOG_TARGET,
+                "Hash exists for {}, aborting run",
+                color::id(target_id),
+            );
+
+            println!(
+                "{} {}",
+                label_checkpoint(target_id, Checkpoint::Pass),
+                color::muted("(cached)")
+            );
+
+            print_cache_item(&cache.item);
+
+            if let Err(error) = workspace.cache.touch_hash(&hash).await {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to mark hash {} for {} as recently used: {}",
+                    color::symbol(&hash),
+                    color::id(target_id),
+                    error
+                );
+            }
+
+            return Ok(ActionStatus::Cached);
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            "Hash exists for {}, but its outputs are missing from the cache, running again",
+            color::id(target_id),
+        );
+    }
+
+    if hydrate_outputs_from_remote(&workspace, &project, task, &hash).await {
+        debug!(
+            target: LOG_TARGET,
+            "Hash exists in the remote cache for {}, aborting run",
+            color::id(target_id),
+        );
+
+        println!(
+            "{} {}",
+            label_checkpoint(target_id, Checkpoint::Pass),
+            color::muted("(cached)")
+        );
+
+        // Track the downloaded hash, so that subsequent runs are a local cache hit
+        if cache.item.hash != hash {
+            cache.item.previous_hash = cache.item.hash.clone();
+        }
+
+        cache.item.exit_code = 0;
+        cache.item.hash = hash;
+        cache.item.last_run_time = cache.now_millis();
+        cache.item.stderr = String::new();
+        cache.item.stdout = String::new();
+        cache.save().await?;
+
+        return Ok(ActionStatus::Cached);
+    }
+
+    let cache_miss_reasons =
+        get_cache_miss_reasons(&workspace, &hasher, &hash, &cache.item.hash).await;
+
+    debug!(
+        target: LOG_TARGET,
+        "Cache miss for {}: {}",
+        color::id(target_id),
+        cache_miss_reasons.join(", ")
+    );
+
+    action.cache_miss_reasons = Some(cache_miss_reasons);
+
+    let output = run_target_command(action, context, &workspace, &project, task, target_id).await?;
+
+    // Archive outputs into the `.moon/cache`, so that subsequent
+    // builds are faster, and any local outputs can be rehydrated easily.
+    workspace
+        .cache
+        .archive_task_outputs(&hash, &project.root, &get_output_paths(task))
+        .await?;
+
+    // Save the new hash and share it with other machines
     workspace.cache.save_hash(&hash, &hasher).await?;
-    action.attempts = Some(attempts);
+
+    if let Err(error) = workspace.cache.upload_hash_to_remote(&hash).await {
+        warn!(
+            target: LOG_TARGET,
+            "Failed to upload hash {} for {} to the remote cache: {}",
+            color::symbol(&hash),
+            color::id(target_id),
+            error
+        );
+    }
 
     // Write the cache with the result and output
+    if cache.item.hash != hash {
+        cache.item.previous_hash = cache.item.hash.clone();
+    }
+
     cache.item.exit_code = output.status.code().unwrap_or(0);
     cache.item.hash = hash;
     cache.item.last_run_time = cache.now_millis();
@@ -333,9 +653,33 @@ fn print_cache_item(item: &RunTargetState) {
     }
 }
 
-fn print_output_std(output: &Output) {
-    let stderr = output_to_string(&output.stderr);
-    let stdout = output_to_string(&output.stdout);
+// Return the last lines of the output, and how many lines were truncated.
+fn truncate_output(output: &str) -> (String, usize) {
+    let lines = output.lines().collect::<Vec<_>>();
+
+    if lines.len() <= MAX_FAILED_OUTPUT_LINES {
+        return (output.to_owned(), 0);
+    }
+
+    let truncated = lines.len() - MAX_FAILED_OUTPUT_LINES;
+
+    (lines[truncated..].join("\n"), truncated)
+}
+
+fn print_output_std(output: &Output, log_path: Option<&PathBuf>) {
+    let mut stderr = output_to_string(&output.stderr);
+    let mut stdout = output_to_string(&output.stdout);
+    let mut truncated = 0;
+
+    // Avoid dumping huge logs when a log file can be referenced instead
+    if !output.status.success() && log_path.is_some() {
+        let (stderr_tail, stderr_truncated) = truncate_output(&stderr);
+        let (stdout_tail, stdout_truncated) = truncate_output(&stdout);
+
+        stderr = stderr_tail;
+        stdout = stdout_tail;
+        truncated = stderr_truncated + stdout_truncated;
+    }
 
     if !stderr.is_empty() {
         eprintln!("{}", stderr.trim());
@@ -346,6 +690,20 @@ fn print_output_std(output: &Output) {
         println!("{}", stdout.trim());
         println!();
     }
+
+    if truncated > 0 {
+        if let Some(log_path) = log_path {
+            eprintln!(
+                "{}",
+                color::muted_light(format!(
+                    "Output truncated by {} lines, view the full log at {}",
+                    truncated,
+                    color::path(log_path)
+                ))
+            );
+            eprintln!();
+        }
+    }
 }
 
 // Print label *after* output has been captured, so parallel tasks
@@ -362,7 +720,7 @@ fn handle_captured_output(target_id: &str, attempt: &Attempt, attempt_total: u8,
         },
     );
 
-    print_output_std(output);
+    print_output_std(output, attempt.log_path.as_ref());
 }
 
 // Only print the label when the process has failed,
@@ -224,6 +224,8 @@ mod tests {
             fixture.path(),
             &GlobalProjectConfig::default(),
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -256,6 +258,8 @@ mod tests {
             fixture.path(),
             &GlobalProjectConfig::default(),
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -293,6 +297,8 @@ mod tests {
             fixture.path(),
             &GlobalProjectConfig::default(),
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
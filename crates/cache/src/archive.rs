@@ -0,0 +1,252 @@
+use moon_error::{map_io_to_fs_error, MoonError};
+use moon_utils::path;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{self, Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+use tar::{Archive, Builder, EntryType, Header};
+
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// A file within an archive, relative from the archive root.
+#[derive(Debug, PartialEq)]
+pub struct ArchiveEntry {
+    pub data: Vec<u8>,
+
+    pub mode: u32,
+
+    pub path: String,
+}
+
+/// Compress the contents of a single file, for storing in the blob store.
+pub fn compress_blob(data: &[u8]) -> Result<Vec<u8>, MoonError> {
+    zstd::encode_all(data, COMPRESSION_LEVEL).map_err(MoonError::Unknown)
+}
+
+/// Decompress the contents of a single file from the blob store.
+pub fn decompress_blob(data: &[u8]) -> Result<Vec<u8>, MoonError> {
+    zstd::decode_all(data).map_err(MoonError::Unknown)
+}
+
+/// Return a sha256 digest of the contents, for use as its content address.
+pub fn get_digest(data: &[u8]) -> String {
+    let mut sha = Sha256::new();
+
+    sha.update(data);
+
+    format!("{:x}", sha.finalize())
+}
+
+/// Read a file into an entry, with a path relative from the root.
+pub fn read_entry(root: &Path, file: &Path) -> Result<ArchiveEntry, MoonError> {
+    let handle_error = |e: io::Error| map_io_to_fs_error(e, file.to_path_buf());
+    let data = fs::read(file).map_err(handle_error)?;
+
+    #[cfg(unix)]
+    let mode = {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::metadata(file)
+            .map_err(handle_error)?
+            .permissions()
+            .mode()
+            & 0o7777
+    };
+
+    #[cfg(not(unix))]
+    let mode = 0o644;
+
+    Ok(ArchiveEntry {
+        data,
+        mode,
+        path: path::to_virtual_string(file.strip_prefix(root).unwrap())?,
+    })
+}
+
+/// Entries may originate from the remote cache, so ensure their path is relative
+/// and can't escape the destination directory (absolute paths, `..`, etc).
+fn get_safe_entry_path(entry_path: &str) -> Result<PathBuf, MoonError> {
+    let rel_path = PathBuf::from(path::normalize_separators(entry_path));
+
+    if entry_path.is_empty()
+        || rel_path.is_absolute()
+        || rel_path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(MoonError::ArchiveUnsafePath(entry_path.to_owned()));
+    }
+
+    Ok(rel_path)
+}
+
+/// Write an entry into the destination directory, replacing an existing file.
+pub fn write_entry(entry: &ArchiveEntry, dest_root: &Path) -> Result<(), MoonError> {
+    let dest = dest_root.join(get_safe_entry_path(&entry.path)?);
+    let handle_error = |e: io::Error| map_io_to_fs_error(e, dest.clone());
+
+    fs::create_dir_all(dest.parent().unwrap()).map_err(handle_error)?;
+
+    // Remove the file first instead of truncating it, as it may be
+    // a hard link that was created by a previous version of moon
+    if dest.exists() {
+        fs::remove_file(&dest).map_err(handle_error)?;
+    }
+
+    fs::write(&dest, &entry.data).map_err(handle_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(&dest, fs::Permissions::from_mode(entry.mode)).map_err(handle_error)?;
+    }
+
+    Ok(())
+}
+
+/// Create a zstd compressed tarball of the provided entries. Entries are sorted and their
+/// timestamps and ownership are zeroed, so that identical files always produce an identical
+/// archive, regardless of when or where they were created.
+pub fn create_archive(entries: &[ArchiveEntry]) -> Result<Vec<u8>, MoonError> {
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    let mut builder = Builder::new(vec![]);
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries.dedup_by(|a, b| a.path == b.path);
+
+    for entry in entries {
+        let mut header = Header::new_gnu();
+        header.set_entry_type(EntryType::Regular);
+        header.set_size(entry.data.len() as u64);
+        header.set_mtime(0);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_mode(entry.mode);
+
+        builder
+            .append_data(&mut header, &entry.path, &entry.data[..])
+            .map_err(MoonError::Unknown)?;
+    }
+
+    let tar = builder.into_inner().map_err(MoonError::Unknown)?;
+
+    zstd::encode_all(&tar[..], COMPRESSION_LEVEL).map_err(MoonError::Unknown)
+}
+
+/// Read every file entry within the archive.
+pub fn read_archive(data: &[u8]) -> Result<Vec<ArchiveEntry>, MoonError> {
+    let tar = zstd::decode_all(data).map_err(MoonError::Unknown)?;
+    let mut archive = Archive::new(Cursor::new(tar));
+    let mut entries = vec![];
+
+    for entry in archive.entries().map_err(MoonError::Unknown)? {
+        let mut entry = entry.map_err(MoonError::Unknown)?;
+        let mut data = vec![];
+
+        entry.read_to_end(&mut data).map_err(MoonError::Unknown)?;
+
+        entries.push(ArchiveEntry {
+            mode: entry.header().mode().map_err(MoonError::Unknown)?,
+            path: path::to_virtual_string(entry.path().map_err(MoonError::Unknown)?)?,
+            data,
+        });
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_fs::prelude::*;
+
+    #[test]
+    fn creates_identical_archives_for_identical_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+
+        dir.child("a/lib/one.js").write_str("one").unwrap();
+        dir.child("a/lib/two.js").write_str("two").unwrap();
+
+        let a = dir.path().join("a");
+        let one = create_archive(&[
+            read_entry(&a, &a.join("lib/two.js")).unwrap(),
+            read_entry(&a, &a.join("lib/one.js")).unwrap(),
+        ])
+        .unwrap();
+
+        // Different modified times and insertion order
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        dir.child("b/lib/one.js").write_str("one").unwrap();
+        dir.child("b/lib/two.js").write_str("two").unwrap();
+
+        let b = dir.path().join("b");
+        let two = create_archive(&[
+            read_entry(&b, &b.join("lib/one.js")).unwrap(),
+            read_entry(&b, &b.join("lib/two.js")).unwrap(),
+        ])
+        .unwrap();
+
+        assert_eq!(get_digest(&one), get_digest(&two));
+
+        let entries = read_archive(&one).unwrap();
+
+        assert_eq!(
+            entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>(),
+            vec!["lib/one.js", "lib/two.js"]
+        );
+        assert_eq!(entries[1].data, b"two");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn compresses_and_decompresses_blobs() {
+        let blob = compress_blob(b"content").unwrap();
+
+        assert_eq!(decompress_blob(&blob).unwrap(), b"content");
+    }
+
+    #[test]
+    fn writes_and_replaces_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+
+        dir.child("src/lib/one.js").write_str("new").unwrap();
+        dir.child("dest/lib/one.js").write_str("old").unwrap();
+
+        let src = dir.path().join("src");
+        let entry = read_entry(&src, &src.join("lib/one.js")).unwrap();
+
+        write_entry(&entry, &dir.path().join("dest")).unwrap();
+        write_entry(&entry, &dir.path().join("other")).unwrap();
+
+        dir.child("dest/lib/one.js").assert("new");
+        dir.child("other/lib/one.js").assert("new");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn rejects_paths_outside_destination() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let dest = dir.path().join("dest");
+
+        for path in ["../escape", "lib/../../escape", "/abs", ""] {
+            let entry = ArchiveEntry {
+                data: b"content".to_vec(),
+                mode: 0o644,
+                path: path.to_owned(),
+            };
+
+            assert!(matches!(
+                write_entry(&entry, &dest),
+                Err(MoonError::ArchiveUnsafePath(_))
+            ));
+        }
+
+        assert!(!dir.path().join("escape").exists());
+        assert!(!Path::new("/abs").exists());
+
+        dir.close().unwrap();
+    }
+}
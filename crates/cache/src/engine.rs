@@ -1,32 +1,111 @@
-use crate::helpers::{is_writable, LOG_TARGET};
-use crate::items::{CacheItem, ProjectsState, RunTargetState, WorkspaceState};
+use crate::archive::{
+    compress_blob, create_archive, decompress_blob, get_digest, read_archive, read_entry,
+    write_entry, ArchiveEntry,
+};
+use crate::helpers::{is_readable, is_remote_writable, is_writable, LOG_TARGET};
+use crate::items::{
+    CacheItem, HashOutputFile, HashOutputsState, ProjectsState, RunTargetState, WorkspaceState,
+};
+use crate::remote::BoxedRemoteCache;
 use crate::runfiles::CacheRunfile;
 use moon_config::constants::CONFIG_DIRNAME;
-use moon_error::MoonError;
+use moon_error::{map_io_to_fs_error, MoonError};
 use moon_logger::{color, debug, trace};
-use moon_utils::fs;
+use moon_utils::{fs, path, time};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+fn get_file_stem(path: &Path, extension: &str) -> Option<String> {
+    path.file_name()?
+        .to_str()?
+        .strip_suffix(extension)
+        .map(|stem| stem.to_owned())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, MoonError> {
+    std::fs::read(path).map_err(|e| map_io_to_fs_error(e, path.to_path_buf()))
+}
+
+/// Convert the output paths to paths relative from the destination root.
+/// Returns `None` if an output is not within the root.
+fn get_relative_outputs(
+    dest_root: &Path,
+    output_paths: &[PathBuf],
+) -> Result<Option<Vec<String>>, MoonError> {
+    let mut outputs = vec![];
+
+    for output_path in output_paths {
+        match output_path.strip_prefix(dest_root) {
+            Ok(rel_path) => outputs.push(path::to_virtual_string(rel_path)?),
+            Err(_) => return Ok(None),
+        };
+    }
+
+    Ok(Some(outputs))
+}
+
+/// Check whether the file is the output, or is within the output directory.
+fn is_within_output(file: &str, output: &str) -> bool {
+    file == output
+        || file
+            .strip_prefix(output)
+            .map_or(false, |rest| rest.starts_with('/'))
+}
+
+/// Check whether the file is one of the outputs, or is within an output directory.
+fn is_output_file(file: &str, outputs: &[String]) -> bool {
+    outputs.iter().any(|output| is_within_output(file, output))
+}
+
+/// Check that every output (file or directory) exists within the archived files.
+fn has_all_outputs(files: &[&str], outputs: &[String]) -> bool {
+    outputs
+        .iter()
+        .all(|output| files.iter().any(|file| is_within_output(file, output)))
+}
+
+#[derive(Debug, Default)]
+pub struct CacheCleanResult {
+    /// Hashes that were (or would be) deleted, from least to most recently used.
+    pub hashes: Vec<String>,
+
+    /// Amount of output file blobs that were (or would be) deleted.
+    pub blobs: usize,
+
+    /// Total size in bytes that was (or would be) reclaimed.
+    pub bytes: u64,
+}
 
 pub struct CacheEngine {
     /// The `.moon/cache` directory relative to workspace root.
     /// Contains cached items pertaining to runs and processes.
     pub dir: PathBuf,
 
+    /// The `.moon/cache/blobs` directory. Stores the contents of each task output file,
+    /// addressed by their content, so that identical files are stored once.
+    pub blobs_dir: PathBuf,
+
     /// The `.moon/cache/hashes` directory. Stores hash contents.
     pub hashes_dir: PathBuf,
 
     /// The `.moon/cache/runs` directory. Stores run states and runfiles.
     pub runs_dir: PathBuf,
 
-    /// The `.moon/cache/out` directory. Stores task output.
+    /// The `.moon/cache/out` directory. Stores the output file manifest of each hash.
     pub outputs_dir: PathBuf,
+
+    /// An optional remote cache, configured with `remoteCache` in `.moon/workspace.yml`.
+    /// Shares hashes and task outputs between machines.
+    pub remote: Option<BoxedRemoteCache>,
 }
 
 impl CacheEngine {
     pub async fn create(workspace_root: &Path) -> Result<Self, MoonError> {
         let dir = workspace_root.join(CONFIG_DIRNAME).join("cache");
+        let blobs_dir = dir.join("blobs");
         let hashes_dir = dir.join("hashes");
         let runs_dir = dir.join("runs");
         let outputs_dir = dir.join("out");
@@ -37,18 +116,60 @@ impl CacheEngine {
             color::path(&dir)
         );
 
+        fs::create_dir_all(&blobs_dir).await?;
         fs::create_dir_all(&hashes_dir).await?;
         fs::create_dir_all(&runs_dir).await?;
         fs::create_dir_all(&outputs_dir).await?;
 
         Ok(CacheEngine {
             dir,
+            blobs_dir,
             hashes_dir,
             runs_dir,
             outputs_dir,
+            remote: None,
         })
     }
 
+    /// Store each of the task's output files in the blob store, and a manifest of the files
+    /// for the hash, so that the outputs can be restored on a subsequent cache hit.
+    pub async fn archive_task_outputs(
+        &self,
+        hash: &str,
+        source_root: &Path,
+        output_paths: &[PathBuf],
+    ) -> Result<(), MoonError> {
+        if !is_writable() {
+            return Ok(());
+        }
+
+        let mut files = vec![];
+
+        for output_path in output_paths {
+            if output_path.is_file() {
+                files.push(output_path.to_owned());
+            } else if output_path.is_dir() {
+                for entry in fs::read_dir_all(output_path).await? {
+                    files.push(entry.path());
+                }
+            }
+        }
+
+        trace!(
+            target: LOG_TARGET,
+            "Archiving {} outputs for hash {}",
+            files.len(),
+            color::symbol(hash)
+        );
+
+        let entries = files
+            .iter()
+            .map(|file| read_entry(source_root, file))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.save_outputs(hash, entries).await
+    }
+
     pub async fn cache_run_target_state(
         &self,
         target_id: &str,
@@ -64,6 +185,18 @@ impl CacheEngine {
         .await
     }
 
+    pub async fn cache_hash_outputs_state(
+        &self,
+        hash: &str,
+    ) -> Result<CacheItem<HashOutputsState>, MoonError> {
+        CacheItem::load(
+            self.get_hash_outputs_path(hash),
+            HashOutputsState::default(),
+            0,
+        )
+        .await
+    }
+
     pub async fn cache_projects_state(&self) -> Result<CacheItem<ProjectsState>, MoonError> {
         CacheItem::load(
             self.dir.join("projectsState.json"),
@@ -82,6 +215,188 @@ impl CacheEngine {
         .await
     }
 
+    /// Delete hashes that have not been used within the lifetime, and then delete the
+    /// least recently used hashes until the cache is within the maximum size (in bytes).
+    /// Output file blobs are deleted once no remaining hash references them.
+    pub async fn clean_stale_cache(
+        &self,
+        lifetime: Option<Duration>,
+        max_size: Option<u64>,
+        dry_run: bool,
+    ) -> Result<CacheCleanResult, MoonError> {
+        let now = SystemTime::now();
+        let mut hashes = vec![];
+        let mut blob_refs: HashMap<String, usize> = HashMap::new();
+        let mut blob_sizes: HashMap<String, u64> = HashMap::new();
+        let mut total_size = 0;
+
+        for entry in fs::read_dir(&self.blobs_dir).await? {
+            let path = entry.path();
+            let size = fs::metadata(&path).await?.len();
+
+            if let Some(digest) = get_file_stem(&path, ".zst") {
+                total_size += size;
+                blob_refs.insert(digest.clone(), 0);
+                blob_sizes.insert(digest, size);
+            }
+        }
+
+        for entry in fs::read_dir(&self.hashes_dir).await? {
+            let path = entry.path();
+            let hash = match get_file_stem(&path, ".json") {
+                Some(hash) => hash,
+                None => continue,
+            };
+            let metadata = fs::metadata(&path).await?;
+            let last_used = metadata.modified().unwrap_or(now);
+            let mut size = metadata.len();
+            let mut digests = HashSet::new();
+            let outputs_path = self.get_hash_outputs_path(&hash);
+
+            if outputs_path.exists() {
+                size += fs::metadata(&outputs_path).await?.len();
+                digests = fs::read_json::<_, HashOutputsState>(&outputs_path)
+                    .await
+                    .map(|outputs| outputs.files.into_iter().map(|file| file.digest).collect())
+                    .unwrap_or_default();
+
+                for digest in &digests {
+                    if let Some(refs) = blob_refs.get_mut(digest) {
+                        *refs += 1;
+                    }
+                }
+            }
+
+            total_size += size;
+            hashes.push((hash, last_used, size, digests));
+        }
+
+        // Orphaned blobs are always deleted below, so don't count them towards the max size
+        for (digest, refs) in &blob_refs {
+            if *refs == 0 {
+                total_size -= blob_sizes[digest];
+            }
+        }
+
+        // Least recently used first
+        hashes.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut result = CacheCleanResult::default();
+
+        for (hash, last_used, size, digests) in hashes {
+            let is_stale = match lifetime {
+                Some(lifetime) => now.duration_since(last_used).unwrap_or_default() > lifetime,
+                None => false,
+            };
+            let is_over_size = match max_size {
+                Some(max_size) => total_size > max_size,
+                None => false,
+            };
+
+            if !is_stale && !is_over_size {
+                continue;
+            }
+
+            trace!(
+                target: LOG_TARGET,
+                "Cleaning hash {} (last used {} ago)",
+                color::symbol(&hash),
+                time::elapsed(now.duration_since(last_used).unwrap_or_default())
+            );
+
+            total_size -= size;
+            result.bytes += size;
+
+            for digest in &digests {
+                if let Some(refs) = blob_refs.get_mut(digest) {
+                    *refs -= 1;
+
+                    // Only reclaimed once the last hash is removed
+                    if *refs == 0 {
+                        total_size -= blob_sizes[digest];
+                    }
+                }
+            }
+
+            if !dry_run {
+                self.delete_hash(&hash).await?;
+            }
+
+            result.hashes.push(hash);
+        }
+
+        // Delete blobs that are no longer referenced by any hash
+        for (digest, refs) in blob_refs {
+            if refs > 0 {
+                continue;
+            }
+
+            result.blobs += 1;
+            result.bytes += blob_sizes[&digest];
+
+            if !dry_run && is_writable() {
+                fs::remove_file(&self.get_blob_path(&digest)).await?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Write a report of the last pipeline run, so that it may be consumed by other tools.
+    /// Like traces, this is always written, regardless of the cache mode.
+    pub async fn create_run_report<T>(&self, report: &T) -> Result<(), MoonError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let path = self.get_run_report_path();
+
+        trace!(
+            target: LOG_TARGET,
+            "Writing run report {}",
+            color::path(&path)
+        );
+
+        fs::write_json(&path, &report, true).await?;
+
+        Ok(())
+    }
+
+    /// Write a trace of a pipeline run. Unlike other cache items, this is
+    /// always written, as it's only created when explicitly requested.
+    pub async fn create_run_trace<T>(&self, trace: &T) -> Result<PathBuf, MoonError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let path = self.get_run_trace_path();
+
+        trace!(
+            target: LOG_TARGET,
+            "Writing run trace {}",
+            color::path(&path)
+        );
+
+        fs::write_json(&path, &trace, false).await?;
+
+        Ok(path)
+    }
+
+    /// Write the log of a target's attempt into the target's runs directory.
+    pub async fn create_target_log(
+        &self,
+        target_id: &str,
+        attempt: u8,
+        contents: &str,
+    ) -> Result<PathBuf, MoonError> {
+        let path = self.get_target_log_path(target_id, attempt);
+
+        trace!(target: LOG_TARGET, "Writing log {}", color::path(&path));
+
+        fs::create_dir_all(self.get_target_dir(target_id)).await?;
+        fs::write(&path, contents).await?;
+
+        Ok(path)
+    }
+
     pub async fn create_runfile<T: DeserializeOwned + Serialize>(
         &self,
         project_id: &str,
@@ -99,8 +414,30 @@ impl CacheEngine {
             // Remove the hash file itself
             fs::remove_file(&path).await?;
 
-            // And the output with the hash
-            fs::remove_dir_all(&self.get_hash_output_dir(hash)).await?;
+            // And the outputs manifest of the hash. The blobs themselves are not removed,
+            // as they may be shared with other hashes that have identical files.
+            fs::remove_file(&self.get_hash_outputs_path(hash)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete the attempt log files of a target's previous run.
+    pub async fn delete_target_logs(&self, target_id: &str) -> Result<(), MoonError> {
+        let target_dir = self.get_target_dir(target_id);
+
+        if !target_dir.exists() {
+            return Ok(());
+        }
+
+        for entry in fs::read_dir(&target_dir).await? {
+            let path = entry.path();
+
+            if get_file_stem(&path, ".log").is_some() {
+                trace!(target: LOG_TARGET, "Deleting log {}", color::path(&path));
+
+                fs::remove_file(&path).await?;
+            }
         }
 
         Ok(())
@@ -124,49 +461,236 @@ impl CacheEngine {
         Ok(())
     }
 
+    /// Find all hashes in the cache that start with the provided (partial) hash.
+    pub async fn find_hashes(&self, partial_hash: &str) -> Result<Vec<String>, MoonError> {
+        let mut hashes = vec![];
+
+        if partial_hash.is_empty() || !self.hashes_dir.exists() {
+            return Ok(hashes);
+        }
+
+        for entry in fs::read_dir(&self.hashes_dir).await? {
+            if let Some(hash) = get_file_stem(&entry.path(), ".json") {
+                if hash.starts_with(partial_hash) {
+                    hashes.push(hash);
+                }
+            }
+        }
+
+        hashes.sort();
+
+        Ok(hashes)
+    }
+
+    pub fn get_blob_path(&self, digest: &str) -> PathBuf {
+        self.blobs_dir.join(format!("{}.zst", digest))
+    }
+
     pub fn get_hash_path(&self, hash: &str) -> PathBuf {
         self.hashes_dir.join(format!("{}.json", hash))
     }
 
-    pub fn get_hash_output_dir(&self, hash: &str) -> PathBuf {
-        self.outputs_dir.join(hash)
+    pub fn get_hash_outputs_path(&self, hash: &str) -> PathBuf {
+        self.outputs_dir.join(format!("{}.json", hash))
     }
 
     pub fn get_project_dir(&self, project_id: &str) -> PathBuf {
         self.runs_dir.join(project_id)
     }
 
+    pub fn get_run_report_path(&self) -> PathBuf {
+        self.dir.join("runReport.json")
+    }
+
+    pub fn get_run_trace_path(&self) -> PathBuf {
+        self.dir.join("runTrace.json")
+    }
+
+    pub fn get_target_log_path(&self, target_id: &str, attempt: u8) -> PathBuf {
+        self.get_target_dir(target_id)
+            .join(format!("attempt{}.log", attempt))
+    }
+
     pub fn get_target_dir(&self, target_id: &str) -> PathBuf {
         let path: PathBuf = [&target_id.replace(':', "/")].iter().collect();
 
         self.runs_dir.join(path)
     }
 
-    pub async fn link_task_output_to_out(
+    /// Restore the task's outputs from the blob store into the project, so that a cache hit
+    /// results in the same files as a run. Returns false if the hash has not been archived,
+    /// or an output or blob is missing, as the outputs can not be fully restored.
+    pub async fn hydrate_task_outputs(
         &self,
         hash: &str,
-        source_root: &Path,
-        source_path: &Path,
-    ) -> Result<(), MoonError> {
-        if is_writable() {
-            let dest_root = self.get_hash_output_dir(hash);
+        dest_root: &Path,
+        output_paths: &[PathBuf],
+    ) -> Result<bool, MoonError> {
+        if output_paths.is_empty() {
+            return Ok(true);
+        }
+
+        let outputs = self.cache_hash_outputs_state(hash).await?;
+        let files = outputs
+            .item
+            .files
+            .iter()
+            .map(|file| file.path.as_str())
+            .collect::<Vec<_>>();
+
+        let rel_outputs = match get_relative_outputs(dest_root, output_paths)? {
+            Some(outputs) => outputs,
+            None => return Ok(false),
+        };
+
+        if files.is_empty() || !has_all_outputs(&files, &rel_outputs) {
+            return Ok(false);
+        }
+
+        // Read all blobs upfront, so that outputs are not partially restored
+        let entries = match self.read_outputs(&outputs.item.files)? {
+            Some(entries) => entries,
+            None => return Ok(false),
+        };
+
+        trace!(
+            target: LOG_TARGET,
+            "Hydrating {} outputs for hash {}",
+            entries.len(),
+            color::symbol(hash),
+        );
+
+        for entry in &entries {
+            write_entry(entry, dest_root)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Download the hash manifest and its archived outputs from the remote cache, and restore
+    /// the task's outputs into the project. The hash is only persisted into the local cache
+    /// when writable, so that read-only modes can consume the remote cache without side effects.
+    /// Returns false if the remote cache does not have the hash, or it's missing an output.
+    pub async fn hydrate_task_outputs_from_remote(
+        &self,
+        hash: &str,
+        dest_root: &Path,
+        output_paths: &[PathBuf],
+    ) -> Result<bool, MoonError> {
+        let remote = match &self.remote {
+            Some(remote) if is_readable() => remote,
+            _ => return Ok(false),
+        };
+
+        let manifest = match remote.get(&format!("{}.json", hash)).await? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+
+        let archive = match remote.get(&format!("{}.tar.zst", hash)).await? {
+            Some(data) => data,
+            None => return Ok(false),
+        };
+
+        trace!(
+            target: "moon:cache:remote",
+            "Downloaded hash {} from {}",
+            color::symbol(hash),
+            color::url(remote.get_location())
+        );
 
-            fs::create_dir_all(&dest_root).await?;
+        let outputs = match get_relative_outputs(dest_root, output_paths)? {
+            Some(outputs) => outputs,
+            None => return Ok(false),
+        };
+
+        // The remote cache is not trusted, so only restore the task's declared outputs
+        let entries = read_archive(&archive)?
+            .into_iter()
+            .filter(|entry| is_output_file(&entry.path, &outputs))
+            .collect::<Vec<_>>();
+        let files = entries
+            .iter()
+            .map(|entry| entry.path.as_str())
+            .collect::<Vec<_>>();
+
+        if !has_all_outputs(&files, &outputs) {
+            return Ok(false);
+        }
 
+        if !output_paths.is_empty() {
             trace!(
                 target: LOG_TARGET,
-                "Hardlinking output {} to {}",
-                color::path(source_path),
-                color::path(&dest_root)
+                "Hydrating outputs for hash {} from the remote cache",
+                color::symbol(hash),
             );
 
-            if source_path.is_file() {
-                fs::link_file(source_root, source_path, &dest_root).await?;
-            } else {
-                fs::link_dir(source_root, source_path, &dest_root).await?;
+            for entry in &entries {
+                write_entry(entry, dest_root)?;
+            }
+        }
+
+        if is_writable() {
+            fs::write(&self.get_hash_path(hash), manifest).await?;
+
+            self.save_outputs(hash, entries).await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Read the contents of each output file from the blob store.
+    /// Returns `None` if a blob is missing.
+    fn read_outputs(
+        &self,
+        files: &[HashOutputFile],
+    ) -> Result<Option<Vec<ArchiveEntry>>, MoonError> {
+        let mut entries = vec![];
+
+        for file in files {
+            let blob_path = self.get_blob_path(&file.digest);
+
+            if !blob_path.exists() {
+                return Ok(None);
+            }
+
+            entries.push(ArchiveEntry {
+                data: decompress_blob(&read_file(&blob_path)?)?,
+                mode: file.mode,
+                path: file.path.clone(),
+            });
+        }
+
+        Ok(Some(entries))
+    }
+
+    /// Write each file to the blob store, unless an identical file already exists,
+    /// and point the hash's outputs manifest to them.
+    async fn save_outputs(&self, hash: &str, entries: Vec<ArchiveEntry>) -> Result<(), MoonError> {
+        let mut files = vec![];
+
+        for entry in entries {
+            let digest = get_digest(&entry.data);
+            let blob_path = self.get_blob_path(&digest);
+
+            if !blob_path.exists() {
+                fs::write(&blob_path, compress_blob(&entry.data)?).await?;
             }
+
+            files.push(HashOutputFile {
+                digest,
+                mode: entry.mode,
+                path: entry.path,
+            });
         }
 
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files.dedup_by(|a, b| a.path == b.path);
+
+        let mut outputs = self.cache_hash_outputs_state(hash).await?;
+        outputs.item.files = files;
+        outputs.save().await?;
+
         Ok(())
     }
 
@@ -184,6 +708,62 @@ impl CacheEngine {
 
         Ok(())
     }
+
+    /// Mark the hash as recently used, so that it is not cleaned
+    /// before hashes that have not been used in a while.
+    pub async fn touch_hash(&self, hash: &str) -> Result<(), MoonError> {
+        if is_writable() {
+            let path = self.get_hash_path(hash);
+
+            if path.exists() {
+                filetime::set_file_mtime(&path, filetime::FileTime::now())
+                    .map_err(|e| map_io_to_fs_error(e, path.clone()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upload the hash manifest and an archive of its outputs to the remote cache,
+    /// so that other machines can skip running the target.
+    pub async fn upload_hash_to_remote(&self, hash: &str) -> Result<(), MoonError> {
+        let remote = match &self.remote {
+            Some(remote) if is_remote_writable() => remote,
+            _ => return Ok(()),
+        };
+
+        let manifest_path = self.get_hash_path(hash);
+        let outputs = self.cache_hash_outputs_state(hash).await?;
+
+        if !manifest_path.exists() || outputs.item.files.is_empty() {
+            return Ok(());
+        }
+
+        // Combine the blobs into a single archive, so that the remote
+        // cache only stores 1 portable artifact per hash
+        let entries = match self.read_outputs(&outputs.item.files)? {
+            Some(entries) => entries,
+            None => return Ok(()),
+        };
+
+        // Upload the archive first, as the manifest is used to determine
+        // whether the hash exists, and must only appear once complete
+        remote
+            .put(&format!("{}.tar.zst", hash), create_archive(&entries)?)
+            .await?;
+        remote
+            .put(&format!("{}.json", hash), read_file(&manifest_path)?)
+            .await?;
+
+        trace!(
+            target: "moon:cache:remote",
+            "Uploaded hash {} to {}",
+            color::symbol(hash),
+            color::url(remote.get_location())
+        );
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -205,6 +785,7 @@ mod tests {
             CacheEngine::create(dir.path()).await.unwrap();
 
             assert!(dir.path().join(".moon/cache").exists());
+            assert!(dir.path().join(".moon/cache/blobs").exists());
             assert!(dir.path().join(".moon/cache/hashes").exists());
             assert!(dir.path().join(".moon/cache/runs").exists());
             assert!(dir.path().join(".moon/cache/out").exists());
@@ -256,12 +837,12 @@ mod tests {
                 .write_str("{}")
                 .unwrap();
 
-            dir.child(".moon/cache/out/abc123/file.js")
-                .write_str("")
+            dir.child(".moon/cache/out/abc123.json")
+                .write_str("{}")
                 .unwrap();
 
             let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
+            let out_file = cache.outputs_dir.join("abc123.json");
 
             assert!(hash_file.exists());
             assert!(out_file.exists());
@@ -284,12 +865,12 @@ mod tests {
                 .write_str("{}")
                 .unwrap();
 
-            dir.child(".moon/cache/out/abc123/file.js")
-                .write_str("")
+            dir.child(".moon/cache/out/abc123.json")
+                .write_str("{}")
                 .unwrap();
 
             let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
+            let out_file = cache.outputs_dir.join("abc123.json");
 
             assert!(hash_file.exists());
             assert!(out_file.exists());
@@ -306,30 +887,518 @@ mod tests {
 
         #[tokio::test]
         #[serial]
-        async fn doesnt_delete_if_cache_readonly() {
+        async fn doesnt_delete_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/out/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            let hash_file = cache.hashes_dir.join("abc123.json");
+            let out_file = cache.outputs_dir.join("abc123.json");
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            run_with_env("read", || cache.delete_hash("abc123"))
+                .await
+                .unwrap();
+
+            assert!(hash_file.exists());
+            assert!(out_file.exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod find_hashes {
+        use super::*;
+        use moon_utils::string_vec;
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_matching_hashes() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/hashes/abc456.json")
+                .write_str("{}")
+                .unwrap();
+
+            dir.child(".moon/cache/hashes/def789.json")
+                .write_str("{}")
+                .unwrap();
+
+            assert_eq!(
+                cache.find_hashes("abc").await.unwrap(),
+                string_vec!["abc123", "abc456"]
+            );
+            assert_eq!(
+                cache.find_hashes("def789").await.unwrap(),
+                string_vec!["def789"]
+            );
+            assert!(cache.find_hashes("xyz").await.unwrap().is_empty());
+            assert!(cache.find_hashes("").await.unwrap().is_empty());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod archive_task_outputs {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn archives_files_and_folders() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+
+            dir.child("project/file.js").write_str("").unwrap();
+            dir.child("project/lib/one.js").write_str("").unwrap();
+            dir.child("project/lib/nested/two.js")
+                .write_str("")
+                .unwrap();
+
+            cache
+                .archive_task_outputs(
+                    "abc123",
+                    &project_root,
+                    &[project_root.join("file.js"), project_root.join("lib")],
+                )
+                .await
+                .unwrap();
+
+            let outputs = cache.cache_hash_outputs_state("abc123").await.unwrap();
+
+            assert_eq!(
+                outputs
+                    .item
+                    .files
+                    .iter()
+                    .map(|file| file.path.as_str())
+                    .collect::<Vec<_>>(),
+                vec!["file.js", "lib/nested/two.js", "lib/one.js"]
+            );
+
+            for file in &outputs.item.files {
+                assert!(cache.get_blob_path(&file.digest).exists());
+            }
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn dedupes_identical_outputs() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            dir.child("project/lib/one.js").write_str("one").unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+            cache
+                .archive_task_outputs("def456", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            let a = cache.cache_hash_outputs_state("abc123").await.unwrap();
+            let b = cache.cache_hash_outputs_state("def456").await.unwrap();
+
+            assert_eq!(a.item.files, b.item.files);
+            assert_eq!(fs::read_dir(&cache.blobs_dir).unwrap().count(), 1);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn dedupes_identical_files_across_outputs() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            dir.child("project/lib/one.js").write_str("one").unwrap();
+            dir.child("project/lib/two.js").write_str("two").unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            // Only a single file changes
+            dir.child("project/lib/two.js")
+                .write_str("changed")
+                .unwrap();
+
+            cache
+                .archive_task_outputs("def456", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            let a = cache.cache_hash_outputs_state("abc123").await.unwrap();
+            let b = cache.cache_hash_outputs_state("def456").await.unwrap();
+
+            assert_eq!(a.item.files[0], b.item.files[0]);
+            assert_ne!(a.item.files[1].digest, b.item.files[1].digest);
+            assert_eq!(fs::read_dir(&cache.blobs_dir).unwrap().count(), 3);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_archive_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("file.js")];
+
+            dir.child("project/file.js").write_str("").unwrap();
+
+            run_with_env("read", || {
+                cache.archive_task_outputs("abc123", &project_root, &output_paths)
+            })
+            .await
+            .unwrap();
+
+            assert!(!cache.get_hash_outputs_path("abc123").exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod hydrate_task_outputs {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn restores_files_and_folders() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("file.js"), project_root.join("lib")];
+
+            dir.child("project/file.js").write_str("file").unwrap();
+            dir.child("project/lib/nested/two.js")
+                .write_str("two")
+                .unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            fs::remove_file(project_root.join("file.js")).unwrap();
+            fs::remove_dir_all(project_root.join("lib")).unwrap();
+
+            assert!(cache
+                .hydrate_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap());
+
+            dir.child("project/file.js").assert("file");
+            dir.child("project/lib/nested/two.js").assert("two");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_not_archived() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+
+            assert!(!cache
+                .hydrate_task_outputs("abc123", &project_root, &[project_root.join("lib")])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_output_missing_from_archive() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+
+            dir.child("project/lib/one.js").write_str("").unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &[project_root.join("lib")])
+                .await
+                .unwrap();
+
+            assert!(!cache
+                .hydrate_task_outputs(
+                    "abc123",
+                    &project_root,
+                    &[project_root.join("lib"), project_root.join("esm")]
+                )
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_blob_missing() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            dir.child("project/lib/one.js").write_str("one").unwrap();
+            dir.child("project/lib/two.js").write_str("two").unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            let outputs = cache.cache_hash_outputs_state("abc123").await.unwrap();
+
+            fs::remove_file(cache.get_blob_path(&outputs.item.files[1].digest)).unwrap();
+            fs::write(project_root.join("lib/one.js"), "changed").unwrap();
+
+            assert!(!cache
+                .hydrate_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap());
+
+            // Not partially restored
+            dir.child("project/lib/one.js").assert("changed");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn errors_if_manifest_path_escapes_project() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            dir.child("project/lib/one.js").write_str("one").unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            let mut outputs = cache.cache_hash_outputs_state("abc123").await.unwrap();
+            outputs.item.files[0].path = "lib/../../escape.js".into();
+            outputs.save().await.unwrap();
+
+            assert!(cache
+                .hydrate_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .is_err());
+            assert!(!dir.path().join("escape.js").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_true_if_no_outputs() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            assert!(cache
+                .hydrate_task_outputs("abc123", dir.path(), &[])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_mutate_archive_when_project_file_changes() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            dir.child("project/lib/one.js")
+                .write_str("original")
+                .unwrap();
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            // Rewrite the file in place, like a later build would
+            fs::write(project_root.join("lib/one.js"), "mutated").unwrap();
+
+            cache
+                .hydrate_task_outputs("abc123", &project_root, &output_paths)
+                .await
+                .unwrap();
+
+            dir.child("project/lib/one.js").assert("original");
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod create_run_report {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn creates_report_file() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            run_with_env("", || cache.create_run_report(&["action"]))
+                .await
+                .unwrap();
+
+            assert_eq!(
+                fs::read_to_string(dir.path().join(".moon/cache/runReport.json")).unwrap(),
+                "[\n  \"action\"\n]"
+            );
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn creates_even_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            run_with_env("off", || cache.create_run_report(&["action"]))
+                .await
+                .unwrap();
+
+            assert!(cache.get_run_report_path().exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod create_run_trace {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn creates_trace_file() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            let path = run_with_env("", || cache.create_run_trace(&["event"]))
+                .await
+                .unwrap();
+
+            assert_eq!(path, dir.path().join(".moon/cache/runTrace.json"));
+            assert_eq!(fs::read_to_string(path).unwrap(), "[\"event\"]");
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn creates_even_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            run_with_env("off", || cache.create_run_trace(&["event"]))
+                .await
+                .unwrap();
+
+            assert!(cache.get_run_trace_path().exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod create_target_log {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn creates_log_file() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let path = cache
+                .create_target_log("foo:bar", 2, "output")
+                .await
+                .unwrap();
+
+            assert_eq!(
+                path,
+                dir.path().join(".moon/cache/runs/foo/bar/attempt2.log")
+            );
+            assert_eq!(fs::read_to_string(path).unwrap(), "output");
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod delete_target_logs {
+        use super::*;
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_only_logs() {
             let dir = assert_fs::TempDir::new().unwrap();
             let cache = CacheEngine::create(dir.path()).await.unwrap();
 
-            dir.child(".moon/cache/hashes/abc123.json")
-                .write_str("{}")
+            dir.child(".moon/cache/runs/foo/bar/attempt1.log")
+                .write_str("")
                 .unwrap();
 
-            dir.child(".moon/cache/out/abc123/file.js")
+            dir.child(".moon/cache/runs/foo/bar/attempt2.log")
                 .write_str("")
                 .unwrap();
 
-            let hash_file = cache.hashes_dir.join("abc123.json");
-            let out_file = cache.outputs_dir.join("abc123/file.js");
+            dir.child(".moon/cache/runs/foo/bar/lastRunState.json")
+                .write_str("{}")
+                .unwrap();
 
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
+            cache.delete_target_logs("foo:bar").await.unwrap();
 
-            run_with_env("read", || cache.delete_hash("abc123"))
-                .await
-                .unwrap();
+            assert!(!cache.get_target_log_path("foo:bar", 1).exists());
+            assert!(!cache.get_target_log_path("foo:bar", 2).exists());
+            assert!(dir
+                .path()
+                .join(".moon/cache/runs/foo/bar/lastRunState.json")
+                .exists());
 
-            assert!(hash_file.exists());
-            assert!(out_file.exists());
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn ignores_missing_dir() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            cache.delete_target_logs("foo:bar").await.unwrap();
 
             dir.close().unwrap();
         }
@@ -463,7 +1532,7 @@ mod tests {
 
             assert_eq!(
                 fs::read_to_string(item.path).unwrap(),
-                r#"{"exitCode":123,"hash":"","lastRunTime":0,"stderr":"","stdout":"","target":"foo:bar"}"#
+                r#"{"exitCode":123,"hash":"","lastRunTime":0,"previousHash":"","stderr":"","stdout":"","target":"foo:bar"}"#
             );
 
             dir.close().unwrap();
@@ -794,4 +1863,489 @@ mod tests {
             dir.close().unwrap();
         }
     }
+
+    mod remote {
+        use super::*;
+        use crate::remote::FileSystemRemoteCache;
+
+        async fn create_cache_with_remote(root: &Path, remote_dir: &Path) -> CacheEngine {
+            let mut cache = CacheEngine::create(root).await.unwrap();
+            cache.remote = Some(Box::new(FileSystemRemoteCache::new(remote_dir)));
+            cache
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn uploads_and_downloads_hash() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+
+            dir.child("one/.moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+            dir.child("one/project/lib/file.js")
+                .write_str("content")
+                .unwrap();
+
+            let cache = create_cache_with_remote(&dir.path().join("one"), &remote_dir).await;
+            let project_root = dir.path().join("one/project");
+
+            cache
+                .archive_task_outputs("abc123", &project_root, &[project_root.join("lib")])
+                .await
+                .unwrap();
+
+            cache.upload_hash_to_remote("abc123").await.unwrap();
+
+            assert!(remote_dir.join("abc123.json").exists());
+            assert!(remote_dir.join("abc123.tar.zst").exists());
+
+            let cache = create_cache_with_remote(&dir.path().join("two"), &remote_dir).await;
+            let project_root = dir.path().join("two/project");
+
+            assert!(cache
+                .hydrate_task_outputs_from_remote(
+                    "abc123",
+                    &project_root,
+                    &[project_root.join("lib")]
+                )
+                .await
+                .unwrap());
+            assert!(cache.hashes_dir.join("abc123.json").exists());
+
+            dir.child("two/project/lib/file.js").assert("content");
+
+            // Subsequent hydrations are from the local cache
+            assert!(cache
+                .hydrate_task_outputs("abc123", &project_root, &[project_root.join("lib")])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_missing_from_remote() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = create_cache_with_remote(dir.path(), &dir.path().join("remote")).await;
+
+            assert!(!cache
+                .hydrate_task_outputs_from_remote("abc123", dir.path(), &[])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn returns_false_if_no_remote() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            assert!(!cache
+                .hydrate_task_outputs_from_remote("abc123", dir.path(), &[])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_upload_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+            let cache = create_cache_with_remote(dir.path(), &remote_dir).await;
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+
+            run_with_env("read", || cache.upload_hash_to_remote("abc123"))
+                .await
+                .unwrap();
+
+            assert!(!remote_dir.join("abc123.json").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_download_if_cache_off() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+            let cache = create_cache_with_remote(dir.path(), &remote_dir).await;
+
+            dir.child("remote/abc123.json").write_str("{}").unwrap();
+            dir.child("remote/abc123.tar.zst").write_str("").unwrap();
+
+            assert!(
+                !run_with_env("off", || cache.hydrate_task_outputs_from_remote(
+                    "abc123",
+                    dir.path(),
+                    &[]
+                ))
+                .await
+                .unwrap()
+            );
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_persist_download_if_cache_readonly() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+            let project_root = dir.path().join("project");
+
+            dir.child("source/lib/file.js")
+                .write_str("content")
+                .unwrap();
+            dir.child("remote/abc123.json").write_str("{}").unwrap();
+
+            std::fs::write(
+                remote_dir.join("abc123.tar.zst"),
+                create_archive(&[read_entry(
+                    &dir.path().join("source"),
+                    &dir.path().join("source/lib/file.js"),
+                )
+                .unwrap()])
+                .unwrap(),
+            )
+            .unwrap();
+
+            let cache = create_cache_with_remote(dir.path(), &remote_dir).await;
+            let output_paths = [project_root.join("lib")];
+
+            assert!(
+                run_with_env("read", || cache.hydrate_task_outputs_from_remote(
+                    "abc123",
+                    &project_root,
+                    &output_paths
+                ))
+                .await
+                .unwrap()
+            );
+
+            dir.child("project/lib/file.js").assert("content");
+            assert!(!cache.hashes_dir.join("abc123.json").exists());
+            assert!(!cache.outputs_dir.join("abc123.json").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn only_restores_outputs_from_remote() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+            let project_root = dir.path().join("project");
+
+            dir.child("remote/abc123.json").write_str("{}").unwrap();
+
+            std::fs::write(
+                remote_dir.join("abc123.tar.zst"),
+                create_archive(&[
+                    ArchiveEntry {
+                        data: b"content".to_vec(),
+                        mode: 0o644,
+                        path: "lib/file.js".into(),
+                    },
+                    ArchiveEntry {
+                        data: b"content".to_vec(),
+                        mode: 0o644,
+                        path: "library/file.js".into(),
+                    },
+                    ArchiveEntry {
+                        data: b"content".to_vec(),
+                        mode: 0o644,
+                        path: "package.json".into(),
+                    },
+                ])
+                .unwrap(),
+            )
+            .unwrap();
+
+            let cache = create_cache_with_remote(dir.path(), &remote_dir).await;
+
+            assert!(cache
+                .hydrate_task_outputs_from_remote(
+                    "abc123",
+                    &project_root,
+                    &[project_root.join("lib")]
+                )
+                .await
+                .unwrap());
+
+            dir.child("project/lib/file.js").assert("content");
+            assert!(!project_root.join("library/file.js").exists());
+            assert!(!project_root.join("package.json").exists());
+
+            let outputs = cache.cache_hash_outputs_state("abc123").await.unwrap();
+
+            assert_eq!(outputs.item.files.len(), 1);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_upload_if_cache_read_remote_only() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let remote_dir = dir.path().join("remote");
+
+            dir.child(".moon/cache/hashes/abc123.json")
+                .write_str("{}")
+                .unwrap();
+            dir.child("project/lib/file.js")
+                .write_str("content")
+                .unwrap();
+
+            let cache = create_cache_with_remote(dir.path(), &remote_dir).await;
+            let project_root = dir.path().join("project");
+            let output_paths = [project_root.join("lib")];
+
+            run_with_env("read-remote-only", || {
+                cache.archive_task_outputs("abc123", &project_root, &output_paths)
+            })
+            .await
+            .unwrap();
+
+            run_with_env("read-remote-only", || cache.upload_hash_to_remote("abc123"))
+                .await
+                .unwrap();
+
+            assert!(cache.outputs_dir.join("abc123.json").exists());
+            assert!(!remote_dir.join("abc123.json").exists());
+
+            dir.close().unwrap();
+        }
+    }
+
+    mod clean_stale_cache {
+        use super::*;
+        use filetime::{set_file_mtime, FileTime};
+
+        const DAY: u64 = 86400;
+
+        async fn create_hash(cache: &CacheEngine, hash: &str, content: &str, age: u64) {
+            let project_root = cache.dir.join("project");
+            let file = project_root.join(format!("{}.js", hash));
+
+            fs::create_dir_all(&project_root).unwrap();
+            fs::write(&file, content).unwrap();
+            fs::write(cache.get_hash_path(hash), "{}").unwrap();
+
+            cache
+                .archive_task_outputs(hash, &project_root, &[file])
+                .await
+                .unwrap();
+
+            set_file_mtime(
+                cache.get_hash_path(hash),
+                FileTime::from_unix_time(
+                    to_millis(SystemTime::now()) as i64 / 1000 - age as i64,
+                    0,
+                ),
+            )
+            .unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_hashes_older_than_lifetime() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&cache, "old", "a", DAY * 10).await;
+            create_hash(&cache, "new", "b", DAY).await;
+
+            let result = cache
+                .clean_stale_cache(Some(Duration::from_secs(DAY * 7)), None, false)
+                .await
+                .unwrap();
+
+            assert_eq!(result.hashes, vec!["old"]);
+            assert_eq!(result.blobs, 1);
+            assert!(!cache.get_hash_path("old").exists());
+            assert!(!cache.get_hash_outputs_path("old").exists());
+            assert!(cache.get_hash_path("new").exists());
+            assert_eq!(fs::read_dir(&cache.blobs_dir).unwrap().count(), 1);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_least_recently_used_until_within_size() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&cache, "one", "1", DAY * 3).await;
+            create_hash(&cache, "two", "2", DAY * 2).await;
+            create_hash(&cache, "three", "3", DAY).await;
+
+            // Only enough room for the most recently used hash
+            let outputs = cache.cache_hash_outputs_state("three").await.unwrap();
+            let max_size = [
+                cache.get_hash_path("three"),
+                cache.get_hash_outputs_path("three"),
+                cache.get_blob_path(&outputs.item.files[0].digest),
+            ]
+            .iter()
+            .map(|path| fs::metadata(path).unwrap().len())
+            .sum();
+
+            let result = cache
+                .clean_stale_cache(None, Some(max_size), false)
+                .await
+                .unwrap();
+
+            assert_eq!(result.hashes, vec!["one", "two"]);
+            assert!(cache.get_hash_path("three").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_count_orphaned_blobs_towards_size() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&cache, "one", "1", DAY).await;
+
+            dir.child(".moon/cache/blobs/abc123.zst")
+                .write_str(&"x".repeat(1024))
+                .unwrap();
+
+            // Enough room for the hash, but not the orphaned blob
+            let outputs = cache.cache_hash_outputs_state("one").await.unwrap();
+            let max_size = [
+                cache.get_hash_path("one"),
+                cache.get_hash_outputs_path("one"),
+                cache.get_blob_path(&outputs.item.files[0].digest),
+            ]
+            .iter()
+            .map(|path| fs::metadata(path).unwrap().len())
+            .sum();
+
+            let result = cache
+                .clean_stale_cache(None, Some(max_size), false)
+                .await
+                .unwrap();
+
+            assert!(result.hashes.is_empty());
+            assert_eq!(result.blobs, 1);
+            assert!(cache.get_hash_path("one").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn keeps_shared_blobs_until_unreferenced() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+            let project_root = cache.dir.join("project");
+
+            fs::create_dir_all(&project_root).unwrap();
+            fs::write(project_root.join("file.js"), "same").unwrap();
+
+            for (hash, age) in [("old", DAY * 10), ("new", DAY)] {
+                fs::write(cache.get_hash_path(hash), "{}").unwrap();
+
+                cache
+                    .archive_task_outputs(hash, &project_root, &[project_root.join("file.js")])
+                    .await
+                    .unwrap();
+
+                set_file_mtime(
+                    cache.get_hash_path(hash),
+                    FileTime::from_unix_time(
+                        to_millis(SystemTime::now()) as i64 / 1000 - age as i64,
+                        0,
+                    ),
+                )
+                .unwrap();
+            }
+
+            let result = cache
+                .clean_stale_cache(Some(Duration::from_secs(DAY * 7)), None, false)
+                .await
+                .unwrap();
+
+            assert_eq!(result.hashes, vec!["old"]);
+            assert_eq!(result.blobs, 0);
+            assert!(cache
+                .hydrate_task_outputs("new", &project_root, &[project_root.join("file.js")])
+                .await
+                .unwrap());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn deletes_orphaned_blobs() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            dir.child(".moon/cache/blobs/abc123.zst")
+                .write_str("")
+                .unwrap();
+
+            let result = cache.clean_stale_cache(None, None, false).await.unwrap();
+
+            assert_eq!(result.blobs, 1);
+            assert!(!cache.get_blob_path("abc123").exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn doesnt_delete_on_dry_run() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&cache, "old", "a", DAY * 10).await;
+
+            let result = cache
+                .clean_stale_cache(Some(Duration::from_secs(DAY * 7)), None, true)
+                .await
+                .unwrap();
+
+            assert_eq!(result.hashes, vec!["old"]);
+            assert!(result.bytes > 0);
+            assert!(cache.get_hash_path("old").exists());
+            assert_eq!(fs::read_dir(&cache.blobs_dir).unwrap().count(), 1);
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        #[serial]
+        async fn touching_marks_as_recently_used() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let cache = CacheEngine::create(dir.path()).await.unwrap();
+
+            create_hash(&cache, "old", "a", DAY * 10).await;
+
+            cache.touch_hash("old").await.unwrap();
+
+            let result = cache
+                .clean_stale_cache(Some(Duration::from_secs(DAY * 7)), None, false)
+                .await
+                .unwrap();
+
+            assert!(result.hashes.is_empty());
+
+            dir.close().unwrap();
+        }
+    }
 }
@@ -8,7 +8,7 @@ static mut LOGGED_WARNING: bool = false;
 
 pub fn get_cache_env_var() -> String {
     if let Ok(var) = env::var("MOON_CACHE") {
-        if var == "off" || var == "read" || var == "write" {
+        if var == "off" || var == "read" || var == "read-remote-only" || var == "write" {
             return var;
         }
 
@@ -34,6 +34,12 @@ pub fn is_readable() -> bool {
 }
 
 pub fn is_writable() -> bool {
+    let var = get_cache_env_var();
+
+    var == "write" || var == "read-remote-only"
+}
+
+pub fn is_remote_writable() -> bool {
     get_cache_env_var() == "write"
 }
 
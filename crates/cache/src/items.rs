@@ -92,6 +92,10 @@ pub struct RunTargetState {
 
     pub last_run_time: u128,
 
+    /// Hash of the run before this one, for diffing why the hash changed.
+    #[serde(default)]
+    pub previous_hash: String,
+
     pub stderr: String,
 
     pub stdout: String,
@@ -118,3 +122,24 @@ pub struct WorkspaceState {
     #[serde(default)]
     pub last_version_check_time: u128,
 }
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashOutputFile {
+    /// Content address (sha256 digest) of the file's blob in `.moon/cache/blobs`.
+    pub digest: String,
+
+    /// Permissions of the file.
+    pub mode: u32,
+
+    /// Path of the file, relative from the project root.
+    pub path: String,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashOutputsState {
+    /// Manifest of every output file, sorted by path.
+    #[serde(default)]
+    pub files: Vec<HashOutputFile>,
+}
@@ -1,8 +1,11 @@
+mod archive;
 mod engine;
 mod helpers;
 mod items;
+mod remote;
 mod runfiles;
 
 pub use engine::CacheEngine;
 pub use helpers::*;
 pub use items::*;
+pub use remote::*;
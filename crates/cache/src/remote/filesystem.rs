@@ -0,0 +1,60 @@
+use crate::remote::RemoteCache;
+use async_trait::async_trait;
+use moon_error::{map_io_to_fs_error, MoonError};
+use moon_logger::{color, trace};
+use moon_utils::{fs, path};
+use std::path::{Path, PathBuf};
+
+const LOG_TARGET: &str = "moon:cache:remote";
+
+/// A remote cache backed by a directory, typically a network mount
+/// or a folder that is persisted between CI jobs.
+pub struct FileSystemRemoteCache {
+    dir: PathBuf,
+}
+
+impl FileSystemRemoteCache {
+    pub fn new(dir: &Path) -> Self {
+        FileSystemRemoteCache {
+            dir: path::normalize(dir),
+        }
+    }
+}
+
+#[async_trait]
+impl RemoteCache for FileSystemRemoteCache {
+    fn get_location(&self) -> String {
+        self.dir.to_string_lossy().to_string()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, MoonError> {
+        let path = self.dir.join(key);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        trace!(target: LOG_TARGET, "Reading {}", color::path(&path));
+
+        let data = std::fs::read(&path).map_err(|e| map_io_to_fs_error(e, path.clone()))?;
+
+        Ok(Some(data))
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), MoonError> {
+        let path = self.dir.join(key);
+        let temp_path = self.dir.join(format!("{}.{}.tmp", key, std::process::id()));
+
+        trace!(target: LOG_TARGET, "Writing {}", color::path(&path));
+
+        fs::create_dir_all(path.parent().unwrap()).await?;
+
+        // Write to a temporary file and then rename, so that other
+        // processes reading the directory never see a partial file
+        fs::write(&temp_path, data).await?;
+
+        std::fs::rename(&temp_path, &path).map_err(|e| map_io_to_fs_error(e, path.clone()))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+use crate::remote::RemoteCache;
+use async_trait::async_trait;
+use moon_error::MoonError;
+use moon_logger::{color, trace};
+use reqwest::{Client, StatusCode};
+
+const LOG_TARGET: &str = "moon:cache:remote";
+
+/// A remote cache backed by an HTTP server, where contents are read
+/// with `GET <url>/<key>` and written with `PUT <url>/<key>`.
+pub struct HttpRemoteCache {
+    client: Client,
+    url: String,
+}
+
+impl HttpRemoteCache {
+    pub fn new(url: &str) -> Self {
+        HttpRemoteCache {
+            client: Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+        }
+    }
+
+    fn get_key_url(&self, key: &str) -> String {
+        format!("{}/{}", self.url, key)
+    }
+}
+
+#[async_trait]
+impl RemoteCache for HttpRemoteCache {
+    fn get_location(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, MoonError> {
+        let url = self.get_key_url(key);
+        let handle_error = |e: reqwest::Error| MoonError::Http(url.clone(), e.to_string());
+
+        trace!(target: LOG_TARGET, "Downloading {}", color::url(&url));
+
+        let response = self.client.get(&url).send().await.map_err(handle_error)?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                Ok(Some(response.bytes().await.map_err(handle_error)?.to_vec()))
+            }
+            status => Err(MoonError::Http(url, status.to_string())),
+        }
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), MoonError> {
+        let url = self.get_key_url(key);
+
+        trace!(target: LOG_TARGET, "Uploading {}", color::url(&url));
+
+        let response = self
+            .client
+            .put(&url)
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| MoonError::Http(url.clone(), e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(MoonError::Http(url, response.status().to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::mock;
+
+    #[tokio::test]
+    async fn gets_contents() {
+        let remote = HttpRemoteCache::new(&format!("{}/cache/", mockito::server_url()));
+        let request = mock("GET", "/cache/get-abc.json").with_body("{}").create();
+
+        assert_eq!(
+            remote.get("get-abc.json").await.unwrap(),
+            Some(b"{}".to_vec())
+        );
+
+        request.assert();
+    }
+
+    #[tokio::test]
+    async fn returns_none_when_not_found() {
+        let remote = HttpRemoteCache::new(&format!("{}/cache", mockito::server_url()));
+        let request = mock("GET", "/cache/missing.json").with_status(404).create();
+
+        assert_eq!(remote.get("missing.json").await.unwrap(), None);
+
+        request.assert();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Http")]
+    async fn errors_on_server_failure() {
+        let remote = HttpRemoteCache::new(&format!("{}/cache", mockito::server_url()));
+        let _request = mock("GET", "/cache/broken.json").with_status(500).create();
+
+        remote.get("broken.json").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn puts_contents() {
+        let remote = HttpRemoteCache::new(&format!("{}/cache", mockito::server_url()));
+        let request = mock("PUT", "/cache/put-abc.tar")
+            .match_body("archive")
+            .with_status(201)
+            .create();
+
+        remote
+            .put("put-abc.tar", b"archive".to_vec())
+            .await
+            .unwrap();
+
+        request.assert();
+    }
+}
@@ -0,0 +1,53 @@
+mod filesystem;
+mod http;
+
+use crate::helpers::LOG_TARGET;
+use async_trait::async_trait;
+pub use filesystem::FileSystemRemoteCache;
+pub use http::HttpRemoteCache;
+use moon_config::{RemoteCacheProvider, WorkspaceConfig};
+use moon_error::MoonError;
+use moon_logger::{color, debug};
+use std::path::Path;
+
+#[async_trait]
+pub trait RemoteCache {
+    /// Human readable location of the remote cache, for use in logs.
+    fn get_location(&self) -> String;
+
+    /// Read the contents stored under the provided key.
+    /// Returns `None` if the key does not exist in the remote cache.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, MoonError>;
+
+    /// Write the contents under the provided key, overwriting any existing contents.
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), MoonError>;
+}
+
+pub type BoxedRemoteCache = Box<dyn RemoteCache + Send + Sync>;
+
+pub struct RemoteCacheLoader {}
+
+impl RemoteCacheLoader {
+    pub fn load(
+        workspace_root: &Path,
+        workspace_config: &WorkspaceConfig,
+    ) -> Option<BoxedRemoteCache> {
+        let remote_config = workspace_config.remote_cache.as_ref()?;
+        let location = &remote_config.location;
+
+        let remote: BoxedRemoteCache = match remote_config.provider {
+            RemoteCacheProvider::Http => Box::new(HttpRemoteCache::new(location)),
+            RemoteCacheProvider::Filesystem => {
+                Box::new(FileSystemRemoteCache::new(&workspace_root.join(location)))
+            }
+        };
+
+        debug!(
+            target: LOG_TARGET,
+            "Using remote cache at {}",
+            color::url(remote.get_location())
+        );
+
+        Some(remote)
+    }
+}
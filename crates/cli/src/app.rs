@@ -1,10 +1,12 @@
 // https://github.com/clap-rs/clap/tree/master/examples/derive_ref#app-attributes
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::commands::bin::BinTools;
+use crate::commands::clean::{parse_lifetime, parse_max_size};
 use crate::commands::init::{InheritProjectsAs, PackageManager};
-use crate::enums::{CacheMode, LogLevel, TouchedStatus};
+use crate::enums::{CacheMode, GraphFormat, LogLevel, TouchedStatus};
 use clap::{Parser, Subcommand};
 use moon_action::ProfileType;
 use moon_project::TargetID;
@@ -18,6 +20,29 @@ const HEADING_PARALLELISM: &str = "Parallelism and distribution";
 
 #[derive(Debug, Subcommand)]
 pub enum QueryCommands {
+    #[clap(
+        name = "hash",
+        about = "Inspect the contents of a generated hash.",
+        long_about = "Inspect the contents of a generated hash. Supports partial hashes, as long as they're unique."
+    )]
+    Hash {
+        #[clap(help = "Hash to inspect")]
+        hash: String,
+    },
+
+    #[clap(
+        name = "hash-diff",
+        about = "Query the difference between two hashes.",
+        long_about = "Query the difference between two hashes, by comparing the contents of each hash manifest. Supports partial hashes, as long as they're unique."
+    )]
+    HashDiff {
+        #[clap(help = "Base hash to compare against")]
+        left: String,
+
+        #[clap(help = "Other hash to compare with")]
+        right: String,
+    },
+
     #[clap(
         name = "projects",
         about = "Query for projects within the project graph.",
@@ -33,6 +58,9 @@ pub enum QueryCommands {
         #[clap(long, help = "Filter projects that match this source path")]
         source: Option<String>,
 
+        #[clap(long, help = "Filter projects that have the following tags")]
+        tags: Option<String>,
+
         #[clap(long, help = "Filter projects that have the following tasks")]
         tasks: Option<String>,
 
@@ -71,6 +99,22 @@ pub enum QueryCommands {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum SyncCommands {
+    #[clap(
+        name = "codeowners",
+        about = "Generate a code owners file from the owner and maintainers of each project.",
+        long_about = "Generate a code owners file from the owner and maintainers of each project. Will write to an existing CODEOWNERS file in a location supported by GitHub and GitLab, otherwise to .github/CODEOWNERS."
+    )]
+    Codeowners {
+        #[clap(
+            long,
+            help = "Fail if the code owners file is out of date, instead of writing it"
+        )]
+        check: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     // ENVIRONMENT
@@ -140,12 +184,43 @@ pub enum Commands {
     // moon dep-graph [target]
     #[clap(
         name = "dep-graph",
-        about = "Display a dependency graph of all tasks and actions in DOT format.",
+        about = "Display a dependency graph of all tasks and actions.",
         alias = "graph"
     )]
     DepGraph {
         #[clap(help = "Target to *only* graph")]
         target: Option<String>,
+
+        #[clap(
+            value_enum,
+            long,
+            help = "Format to output the graph in",
+            default_value_t
+        )]
+        format: GraphFormat,
+
+        #[clap(
+            long,
+            help = "Serve an interactive graph explorer",
+            conflicts_with_all = &["target", "format"]
+        )]
+        serve: bool,
+
+        #[clap(
+            long,
+            help = "Host to serve the explorer on",
+            default_value = "127.0.0.1",
+            requires = "serve"
+        )]
+        host: String,
+
+        #[clap(
+            long,
+            help = "Port to serve the explorer on, or a random port if 0",
+            default_value_t = 0,
+            requires = "serve"
+        )]
+        port: u16,
     },
 
     // moon project <id>
@@ -164,12 +239,43 @@ pub enum Commands {
     // moon project-graph [id]
     #[clap(
         name = "project-graph",
-        about = "Display a graph of projects in DOT format.",
+        about = "Display a graph of projects.",
         alias = "graph"
     )]
     ProjectGraph {
         #[clap(help = "ID of project to *only* graph")]
         id: Option<String>,
+
+        #[clap(
+            value_enum,
+            long,
+            help = "Format to output the graph in",
+            default_value_t
+        )]
+        format: GraphFormat,
+
+        #[clap(
+            long,
+            help = "Serve an interactive graph explorer",
+            conflicts_with_all = &["id", "format"]
+        )]
+        serve: bool,
+
+        #[clap(
+            long,
+            help = "Host to serve the explorer on",
+            default_value = "127.0.0.1",
+            requires = "serve"
+        )]
+        host: String,
+
+        #[clap(
+            long,
+            help = "Port to serve the explorer on, or a random port if 0",
+            default_value_t = 0,
+            requires = "serve"
+        )]
+        port: u16,
     },
 
     // JOBS
@@ -192,6 +298,21 @@ pub enum Commands {
 
         #[clap(long, help = "Total amount of jobs to run", help_heading = HEADING_PARALLELISM)]
         job_total: Option<usize>,
+
+        #[clap(
+            long,
+            help = "Maximum amount of actions to run in parallel",
+            help_heading = HEADING_PARALLELISM
+        )]
+        concurrency: Option<usize>,
+
+        // Debugging
+        #[clap(
+            long,
+            help = "Record a timeline of all actions to .moon/cache/runTrace.json",
+            help_heading = HEADING_DEBUGGING,
+        )]
+        trace: bool,
     },
 
     // moon run [...targets]
@@ -209,6 +330,13 @@ pub enum Commands {
         )]
         dependents: bool,
 
+        #[clap(
+            long,
+            help = "Maximum amount of actions to run in parallel",
+            help_heading = HEADING_PARALLELISM
+        )]
+        concurrency: Option<usize>,
+
         // Debugging
         #[clap(
             value_enum,
@@ -218,6 +346,13 @@ pub enum Commands {
         )]
         profile: Option<ProfileType>,
 
+        #[clap(
+            long,
+            help = "Record a timeline of all actions to .moon/cache/runTrace.json",
+            help_heading = HEADING_DEBUGGING,
+        )]
+        trace: bool,
+
         // Affected
         #[clap(
             long,
@@ -250,8 +385,108 @@ pub enum Commands {
         passthrough: Vec<String>,
     },
 
+    // moon run-many [...targets]
+    #[clap(
+        name = "run-many",
+        about = "Run multiple project tasks and all their dependent tasks."
+    )]
+    RunMany {
+        #[clap(
+            required = true,
+            help = "Targets (project:task) or target patterns (:task, ^:task, ~:task) to run"
+        )]
+        targets: Vec<TargetID>,
+
+        #[clap(
+            long,
+            help = "Run dependents of the same task, as well as dependencies"
+        )]
+        dependents: bool,
+
+        #[clap(
+            long,
+            help = "Maximum amount of actions to run in parallel",
+            help_heading = HEADING_PARALLELISM
+        )]
+        concurrency: Option<usize>,
+
+        // Debugging
+        #[clap(
+            value_enum,
+            long,
+            help = "Record and generate a profile for ran tasks",
+            help_heading = HEADING_DEBUGGING,
+        )]
+        profile: Option<ProfileType>,
+
+        #[clap(
+            long,
+            help = "Record a timeline of all actions to .moon/cache/runTrace.json",
+            help_heading = HEADING_DEBUGGING,
+        )]
+        trace: bool,
+
+        // Affected
+        #[clap(
+            long,
+            help = "Only run targets if affected by touched files",
+            help_heading = HEADING_AFFECTED
+        )]
+        affected: bool,
+
+        #[clap(
+            value_enum,
+            long,
+            help = "Filter affected files based on a touched status",
+            help_heading = HEADING_AFFECTED,
+            default_value_t
+        )]
+        status: TouchedStatus,
+
+        #[clap(
+            long,
+            help = "Determine affected against upstream by comparing against a base revision",
+            help_heading = HEADING_AFFECTED
+        )]
+        upstream: bool,
+
+        // Passthrough args (after --)
+        #[clap(
+            last = true,
+            help = "Arguments to pass through to the underlying command"
+        )]
+        passthrough: Vec<String>,
+    },
+
     // OTHER
 
+    // moon clean
+    #[clap(
+        name = "clean",
+        about = "Clean the workspace and delete any stale or unused artifacts.",
+        long_about = "Clean the workspace and delete any stale or unused artifacts. Hashes and task outputs that have not been used within the lifetime are deleted, followed by the least recently used, until the cache is within the maximum size.",
+        rename_all = "camelCase"
+    )]
+    Clean {
+        #[clap(long, help = "Report what would be deleted, without deleting")]
+        dry_run: bool,
+
+        #[clap(
+            long,
+            help = "Delete artifacts that have not been used within this duration",
+            default_value = "7 days",
+            value_parser = parse_lifetime
+        )]
+        lifetime: Duration,
+
+        #[clap(
+            long,
+            help = "Maximum total size of the cache in megabytes",
+            value_parser = parse_max_size
+        )]
+        max_size: Option<u64>,
+    },
+
     // moon query <operation>
     #[clap(
         name = "query",
@@ -262,6 +497,16 @@ pub enum Commands {
         #[clap(subcommand)]
         command: QueryCommands,
     },
+
+    // moon sync <operation>
+    #[clap(
+        name = "sync",
+        about = "Sync files in the workspace with the project graph."
+    )]
+    Sync {
+        #[clap(subcommand)]
+        command: SyncCommands,
+    },
 }
 
 #[derive(Debug, Parser)]
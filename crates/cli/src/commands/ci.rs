@@ -155,9 +155,11 @@ fn generate_dep_graph(
 
 pub struct CiOptions {
     pub base: Option<String>,
+    pub concurrency: Option<usize>,
     pub head: Option<String>,
     pub job: Option<usize>,
     pub job_total: Option<usize>,
+    pub trace: bool,
 }
 
 pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
@@ -176,6 +178,15 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
     print_header("Running all targets");
 
     let mut runner = ActionRunner::new(workspace);
+
+    if let Some(concurrency) = options.concurrency {
+        runner.concurrency(concurrency);
+    }
+
+    if options.trace {
+        runner.trace();
+    }
+
     let results = runner
         .run(
             dep_graph,
@@ -194,11 +205,15 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
 
     for result in &results {
         let status = match result.status {
+            ActionStatus::Skipped if result.skip_reason.is_some() => color::muted_light("skip"),
             ActionStatus::Passed | ActionStatus::Cached | ActionStatus::Skipped => {
                 color::success("pass")
             }
-            ActionStatus::Failed | ActionStatus::FailedAndAbort => color::failure("fail"),
+            ActionStatus::Failed | ActionStatus::FailedAndAbort | ActionStatus::TimedOut => {
+                color::failure("fail")
+            }
             ActionStatus::Invalid => color::invalid("warn"),
+            ActionStatus::Aborted => color::invalid("abort"),
             _ => color::muted_light("oops"),
         };
 
@@ -212,6 +227,10 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
             meta.push(time::elapsed(duration));
         }
 
+        if matches!(result.status, ActionStatus::TimedOut) {
+            meta.push(String::from("timed out"));
+        }
+
         term.write_line(&format!(
             "{} {} {}",
             status,
@@ -226,12 +245,20 @@ pub async fn ci(options: CiOptions) -> Result<(), Box<dyn std::error::Error>> {
                 color::muted_light(&replace_style_tokens(error))
             ))?;
         }
+
+        if let Some(reason) = &result.skip_reason {
+            term.write_line(&format!("     {}", color::muted_light(reason)))?;
+        }
     }
 
     term.flush()?;
 
     render_result_stats(results, runner.duration.unwrap(), true)?;
 
+    if let Some(signal) = runner.interrupted {
+        safe_exit(signal.exit_code());
+    }
+
     if error_count > 0 {
         safe_exit(1);
     }
@@ -0,0 +1,78 @@
+use moon_cache::is_writable;
+use moon_logger::color;
+use moon_utils::time;
+use moon_workspace::Workspace;
+use std::time::Duration;
+
+pub struct CleanOptions {
+    pub dry_run: bool,
+    pub lifetime: Duration,
+    /// In bytes.
+    pub max_size: Option<u64>,
+}
+
+pub fn parse_lifetime(value: &str) -> Result<Duration, String> {
+    time::parse_duration(value)
+        .ok_or_else(|| String::from("Must be a duration, for example \"7d\" or \"12h\""))
+}
+
+/// Parse a size in megabytes into bytes.
+pub fn parse_max_size(value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|size| size.checked_mul(1024 * 1024))
+        .ok_or_else(|| String::from("Must be a size in megabytes, for example \"500\""))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut index = 0;
+
+    while size >= 1024.0 && index < units.len() - 1 {
+        size /= 1024.0;
+        index += 1;
+    }
+
+    if index == 0 {
+        format!("{}{}", bytes, units[0])
+    } else {
+        format!("{:.1}{}", size, units[index])
+    }
+}
+
+pub async fn clean(options: CleanOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+
+    // Nothing can be deleted when the cache is read-only, so only report
+    let dry_run = options.dry_run || !is_writable();
+
+    let result = workspace
+        .cache
+        .clean_stale_cache(Some(options.lifetime), options.max_size, dry_run)
+        .await?;
+
+    if dry_run {
+        for hash in &result.hashes {
+            println!("{}", color::symbol(hash));
+        }
+
+        println!(
+            "Would clean {} hashes and {} output blobs, reclaiming {}",
+            result.hashes.len(),
+            result.blobs,
+            format_bytes(result.bytes)
+        );
+    } else {
+        println!(
+            "Cleaned {} hashes and {} output blobs, reclaimed {}",
+            result.hashes.len(),
+            result.blobs,
+            format_bytes(result.bytes)
+        );
+    }
+
+    Ok(())
+}
@@ -1,10 +1,21 @@
+use crate::commands::graph_server::{serve_graph, GraphServerOptions};
+use crate::enums::GraphFormat;
 use moon_action_runner::DepGraph;
 use moon_project::Target;
 use moon_workspace::Workspace;
 
-pub async fn dep_graph(target_id: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn dep_graph(
+    target_id: &Option<String>,
+    format: GraphFormat,
+    serve: Option<GraphServerOptions>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
     let projects = workspace.projects;
+
+    if let Some(options) = serve {
+        return serve_graph(&projects, options).await;
+    }
+
     let mut graph = DepGraph::default();
 
     // Preload all projects
@@ -26,7 +37,15 @@ pub async fn dep_graph(target_id: &Option<String>) -> Result<(), Box<dyn std::er
         }
     }
 
-    println!("{}", graph.to_dot());
+    match format {
+        GraphFormat::Dot => println!("{}", graph.to_dot()),
+        GraphFormat::Graphml => println!("{}", graph.to_graphml()),
+        GraphFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&graph.to_json(&projects)?)?
+        ),
+        GraphFormat::Mermaid => println!("{}", graph.to_mermaid()),
+    };
 
     Ok(())
 }
@@ -0,0 +1,117 @@
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use moon_action_runner::DepGraph;
+use moon_logger::color;
+use moon_project::{ProjectGraph, Target};
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use tokio::signal;
+
+const INDEX_TEMPLATE: &str = include_str!("../../templates/graph.html");
+
+pub struct GraphServerOptions {
+    pub host: String,
+    pub port: u16,
+}
+
+pub struct GraphServerState {
+    pub dep_graph: String,
+    pub project_graph: String,
+}
+
+impl GraphServerState {
+    /// Load all projects and their tasks, so that the entire workspace can be explored.
+    pub fn load(projects: &ProjectGraph) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut dep_graph = DepGraph::default();
+
+        projects.load_all()?;
+
+        for project_id in projects.ids() {
+            for task_id in projects.load(&project_id)?.tasks.keys() {
+                dep_graph.run_target(&Target::new(&project_id, task_id)?, projects, None)?;
+            }
+        }
+
+        Ok(GraphServerState {
+            dep_graph: serde_json::to_string(&dep_graph.to_json(projects)?)?,
+            project_graph: serde_json::to_string(&projects.to_json())?,
+        })
+    }
+}
+
+fn create_response(status: StatusCode, content_type: &str, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
+
+pub fn handle_request(request: &Request<Body>, state: &GraphServerState) -> Response<Body> {
+    if request.method() != Method::GET {
+        return create_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "text/plain",
+            String::from("Method not allowed"),
+        );
+    }
+
+    match request.uri().path() {
+        "/" | "/index.html" => create_response(
+            StatusCode::OK,
+            "text/html; charset=utf-8",
+            INDEX_TEMPLATE.to_owned(),
+        ),
+        "/api/dep-graph" => {
+            create_response(StatusCode::OK, "application/json", state.dep_graph.clone())
+        }
+        "/api/project-graph" => create_response(
+            StatusCode::OK,
+            "application/json",
+            state.project_graph.clone(),
+        ),
+        _ => create_response(
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            String::from("Not found"),
+        ),
+    }
+}
+
+/// Serve the interactive graph explorer until the process is interrupted.
+pub async fn serve_graph(
+    projects: &ProjectGraph,
+    options: GraphServerOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = Arc::new(GraphServerState::load(projects)?);
+    let address = SocketAddr::new(options.host.parse::<IpAddr>()?, options.port);
+
+    let service = make_service_fn(move |_| {
+        let state = Arc::clone(&state);
+
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let response = handle_request(&request, &state);
+
+                async move { Ok::<_, Infallible>(response) }
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&address)?.serve(service);
+
+    println!(
+        "Graph explorer running at {} (press Ctrl+C to stop)",
+        color::url(&format!("http://{}", server.local_addr()))
+    );
+
+    server
+        .with_graceful_shutdown(async {
+            signal::ctrl_c().await.ok();
+        })
+        .await?;
+
+    Ok(())
+}
@@ -1,10 +1,13 @@
 pub mod bin;
 pub mod ci;
+pub mod clean;
 pub mod dep_graph;
+pub mod graph_server;
 pub mod init;
 pub mod project;
 pub mod project_graph;
 pub mod query;
 pub mod run;
 pub mod setup;
+pub mod sync;
 pub mod teardown;
@@ -1,15 +1,32 @@
+use crate::commands::graph_server::{serve_graph, GraphServerOptions};
+use crate::enums::GraphFormat;
 use moon_workspace::Workspace;
 
-pub async fn project_graph(project_id: &Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn project_graph(
+    project_id: &Option<String>,
+    format: GraphFormat,
+    serve: Option<GraphServerOptions>,
+) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
 
+    if let Some(options) = serve {
+        return serve_graph(&workspace.projects, options).await;
+    }
+
     if let Some(id) = project_id {
         workspace.projects.load(id)?;
     } else {
         workspace.projects.load_all()?;
     }
 
-    println!("{}", workspace.projects.to_dot());
+    let projects = workspace.projects;
+
+    match format {
+        GraphFormat::Dot => println!("{}", projects.to_dot()),
+        GraphFormat::Graphml => println!("{}", projects.to_graphml()),
+        GraphFormat::Json => println!("{}", serde_json::to_string_pretty(&projects.to_json())?),
+        GraphFormat::Mermaid => println!("{}", projects.to_mermaid()),
+    };
 
     Ok(())
 }
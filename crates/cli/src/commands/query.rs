@@ -1,9 +1,28 @@
+pub use crate::queries::hash::{query_hash, query_hash_diff, QueryHashDiffResult, QueryHashResult};
 pub use crate::queries::projects::{query_projects, QueryProjectsOptions, QueryProjectsResult};
 pub use crate::queries::touched_files::{
     query_touched_files, QueryTouchedFilesOptions, QueryTouchedFilesResult,
 };
 use moon_workspace::Workspace;
 
+pub async fn hash(hash: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let result = query_hash(&workspace, hash).await?;
+
+    println!("{}", serde_json::to_string_pretty(&result)?);
+
+    Ok(())
+}
+
+pub async fn hash_diff(left: &str, right: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let result = query_hash_diff(&workspace, left, right).await?;
+
+    println!("{}", serde_json::to_string_pretty(&result)?);
+
+    Ok(())
+}
+
 pub async fn projects(options: &QueryProjectsOptions) -> Result<(), Box<dyn std::error::Error>> {
     let workspace = Workspace::load().await?;
 
@@ -3,8 +3,9 @@ use crate::queries::touched_files::{query_touched_files, QueryTouchedFilesOption
 use console::Term;
 use moon_action::{Action, ActionContext, ActionStatus, ProfileType};
 use moon_action_runner::{ActionRunner, DepGraph};
-use moon_logger::color;
-use moon_project::Target;
+use moon_logger::{color, map_list, warn};
+use moon_project::{Target, TargetProject};
+use moon_terminal::helpers::safe_exit;
 use moon_terminal::ExtendedTerm;
 use moon_utils::time;
 use moon_workspace::Workspace;
@@ -12,12 +13,16 @@ use std::collections::HashSet;
 use std::string::ToString;
 use std::time::Duration;
 
+const TARGET: &str = "moon:run";
+
 pub struct RunOptions {
     pub affected: bool,
+    pub concurrency: Option<usize>,
     pub dependents: bool,
     pub status: TouchedStatus,
     pub passthrough: Vec<String>,
     pub profile: Option<ProfileType>,
+    pub trace: bool,
     pub upstream: bool,
 }
 
@@ -30,6 +35,9 @@ pub fn render_result_stats(
     let mut pass_count = 0;
     let mut fail_count = 0;
     let mut invalid_count = 0;
+    let mut skipped_count = 0;
+    let mut timed_out_count = 0;
+    let mut aborted_count = 0;
 
     let filtered_results = if in_actions_context {
         results
@@ -49,12 +57,21 @@ pub fn render_result_stats(
                 cached_count += 1;
                 pass_count += 1;
             }
+            ActionStatus::Skipped if result.skip_reason.is_some() => {
+                skipped_count += 1;
+            }
             ActionStatus::Passed | ActionStatus::Skipped => {
                 pass_count += 1;
             }
             ActionStatus::Failed | ActionStatus::FailedAndAbort => {
                 fail_count += 1;
             }
+            ActionStatus::TimedOut => {
+                timed_out_count += 1;
+            }
+            ActionStatus::Aborted => {
+                aborted_count += 1;
+            }
             ActionStatus::Invalid => {
                 invalid_count += 1;
             }
@@ -79,6 +96,18 @@ pub fn render_result_stats(
         counts_message.push(color::failure(format!("{} failed", fail_count)));
     }
 
+    if timed_out_count > 0 {
+        counts_message.push(color::failure(format!("{} timed out", timed_out_count)));
+    }
+
+    if aborted_count > 0 {
+        counts_message.push(color::invalid(format!("{} aborted", aborted_count)));
+    }
+
+    if skipped_count > 0 {
+        counts_message.push(color::muted_light(format!("{} skipped", skipped_count)));
+    }
+
     if invalid_count > 0 {
         counts_message.push(color::invalid(format!("{} invalid", invalid_count)));
     }
@@ -103,13 +132,30 @@ pub fn render_result_stats(
     Ok(())
 }
 
-pub async fn run(target_id: &str, options: RunOptions) -> Result<(), Box<dyn std::error::Error>> {
-    let target = Target::parse(target_id)?;
+pub async fn run(
+    target_ids: &[String],
+    options: RunOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let targets = target_ids
+        .iter()
+        .map(|id| Target::parse(id))
+        .collect::<Result<Vec<Target>, _>>()?;
     let workspace = Workspace::load().await?;
+    let targets_label = map_list(target_ids, |id| color::target(id));
+
+    // Targets with the deps (^) and self (~) scopes are relative to the current project
+    let from_project = match workspace
+        .projects
+        .find_project_id_from_path(&workspace.working_dir)
+    {
+        Some(project_id) => Some(workspace.projects.load(&project_id)?),
+        None => None,
+    };
 
     // Generate a dependency graph for all the targets that need to be ran
     let mut dep_graph = DepGraph::default();
     let mut touched_files = HashSet::new();
+    let mut primary_targets = HashSet::new();
 
     if options.affected {
         touched_files = query_touched_files(
@@ -121,56 +167,116 @@ pub async fn run(target_id: &str, options: RunOptions) -> Result<(), Box<dyn std
             },
         )
         .await?;
+    }
 
-        let inserted_count =
-            dep_graph.run_target(&target, &workspace.projects, Some(&touched_files))?;
-
-        if inserted_count == 0 {
-            if matches!(options.status, TouchedStatus::All) {
-                println!(
-                    "Target {} not affected by touched files",
-                    color::target(target_id)
-                );
+    for target in &targets {
+        primary_targets.extend(dep_graph.run_target_from_project(
+            target,
+            from_project.as_ref(),
+            &workspace.projects,
+            if options.affected {
+                Some(&touched_files)
             } else {
-                println!(
-                    "Target {} not affected by touched files (using status {})",
-                    color::target(target_id),
-                    color::symbol(&options.status.to_string().to_lowercase())
-                );
-            }
-
-            return Ok(());
-        }
-    } else {
-        let inserted_count = dep_graph.run_target(&target, &workspace.projects, None)?;
+                None
+            },
+        )?);
+    }
 
-        if inserted_count == 0 {
-            println!("No tasks found for target {}", color::target(target_id));
+    if primary_targets.is_empty() {
+        let label = if target_ids.len() == 1 {
+            "Target"
+        } else {
+            "Targets"
+        };
 
-            return Ok(());
+        if !options.affected {
+            println!(
+                "No tasks found for {} {}",
+                label.to_lowercase(),
+                targets_label
+            );
+        } else if matches!(options.status, TouchedStatus::All) {
+            println!("{} {} not affected by touched files", label, targets_label);
+        } else {
+            println!(
+                "{} {} not affected by touched files (using status {})",
+                label,
+                targets_label,
+                color::symbol(&options.status.to_string().to_lowercase())
+            );
         }
+
+        return Ok(());
     }
 
     if options.dependents {
         workspace.projects.load_all()?;
 
-        dep_graph.run_target_dependents(&target, &workspace.projects)?;
+        for target in &targets {
+            match &target.project {
+                TargetProject::Id(_) => {
+                    dep_graph.run_target_dependents(target, &workspace.projects)?;
+                }
+                TargetProject::Own => {
+                    if let Some(project) = &from_project {
+                        dep_graph.run_target_dependents(
+                            &Target::new(&project.id, &target.task_id)?,
+                            &workspace.projects,
+                        )?;
+                    }
+                }
+                TargetProject::Tag(tag) => {
+                    for project_id in workspace.projects.ids() {
+                        let project = workspace.projects.load(&project_id)?;
+
+                        if project.config.tags.contains(tag)
+                            && project.tasks.contains_key(&target.task_id)
+                        {
+                            dep_graph.run_target_dependents(
+                                &Target::new(&project_id, &target.task_id)?,
+                                &workspace.projects,
+                            )?;
+                        }
+                    }
+                }
+                TargetProject::All | TargetProject::Deps => {
+                    warn!(
+                        target: TARGET,
+                        "Unable to run dependents of target {}, as --dependents requires a project, self, or tag scope",
+                        color::target(&target.id),
+                    );
+                }
+            };
+        }
     }
 
     // Process all tasks in the graph
     let context = ActionContext {
         passthrough_args: options.passthrough,
-        primary_targets: HashSet::from([target_id.to_owned()]),
+        primary_targets,
         profile: options.profile,
         touched_files,
+        ..ActionContext::default()
     };
 
     let mut runner = ActionRunner::new(workspace);
 
+    if let Some(concurrency) = options.concurrency {
+        runner.concurrency(concurrency);
+    }
+
+    if options.trace {
+        runner.trace();
+    }
+
     let results = runner.bail_on_error().run(dep_graph, context).await?;
 
     // Render stats about the run
     render_result_stats(results, runner.duration.unwrap(), false)?;
 
+    if let Some(signal) = runner.interrupted {
+        safe_exit(signal.exit_code());
+    }
+
     Ok(())
 }
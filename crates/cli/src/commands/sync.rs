@@ -0,0 +1,142 @@
+use moon_logger::{color, debug, warn};
+use moon_project::Project;
+use moon_utils::{fs, path};
+use moon_workspace::{Workspace, WorkspaceError};
+use std::path::{Path, PathBuf};
+
+const TARGET: &str = "moon:sync";
+
+// Locations supported by both GitHub and GitLab, in order of precedence
+const CODEOWNERS_PATHS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+    "docs/CODEOWNERS",
+    "CODEOWNERS",
+];
+
+/// Find an existing code owners file, or fallback to the `.github` location.
+fn find_codeowners_path(workspace_root: &Path) -> PathBuf {
+    CODEOWNERS_PATHS
+        .iter()
+        .map(|file| workspace_root.join(file))
+        .find(|file| file.exists())
+        .unwrap_or_else(|| workspace_root.join(CODEOWNERS_PATHS[0]))
+}
+
+/// Owners must be a username, a team (org/team), or an email address.
+fn format_owner(project: &Project, owner: &str) -> Option<String> {
+    let owner = owner.trim();
+
+    if owner.is_empty() {
+        return None;
+    }
+
+    if owner.contains(char::is_whitespace) {
+        warn!(
+            target: TARGET,
+            "Unable to add \"{}\" as a code owner of project {}, as it's not a username, team, or email",
+            owner,
+            color::id(&project.id),
+        );
+
+        return None;
+    }
+
+    if owner.contains('@') {
+        Some(owner.to_owned())
+    } else {
+        Some(format!("@{}", owner))
+    }
+}
+
+/// Generate the file contents from the owner and maintainers of each project.
+/// Projects are sorted by source, so that nested projects take precedence.
+pub fn generate_codeowners(projects: &[Project]) -> String {
+    let mut projects = projects.iter().collect::<Vec<_>>();
+    let mut sections = vec![String::from(
+        "# Automatically generated by moon from the owner and maintainers of each project.\n# Do not edit manually! Run `moon sync codeowners` to regenerate this file.",
+    )];
+
+    projects.sort_by(|a, b| a.source.cmp(&b.source));
+
+    for project in projects {
+        let metadata = match &project.config.project {
+            Some(metadata) => metadata,
+            None => continue,
+        };
+
+        let mut owners: Vec<String> = vec![];
+
+        for owner in std::iter::once(&metadata.owner).chain(metadata.maintainers.iter()) {
+            if let Some(owner) = format_owner(project, owner) {
+                if !owners.contains(&owner) {
+                    owners.push(owner);
+                }
+            }
+        }
+
+        if owners.is_empty() {
+            continue;
+        }
+
+        let pattern = if project.source == "." {
+            String::from("*")
+        } else {
+            format!("/{}/", path::standardize_separators(&project.source))
+        };
+
+        sections.push(format!(
+            "# {}\n{} {}",
+            project.id,
+            pattern,
+            owners.join(" ")
+        ));
+    }
+
+    format!("{}\n", sections.join("\n\n"))
+}
+
+pub async fn codeowners(check: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = Workspace::load().await?;
+    let codeowners_path = find_codeowners_path(&workspace.root);
+    let rel_path = path::to_virtual_string(codeowners_path.strip_prefix(&workspace.root)?)?;
+
+    workspace.projects.load_all()?;
+
+    let projects = workspace
+        .projects
+        .ids()
+        .iter()
+        .map(|id| workspace.projects.load(id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let contents = generate_codeowners(&projects);
+
+    if check {
+        let current = if codeowners_path.exists() {
+            fs::read(&codeowners_path).await?
+        } else {
+            String::new()
+        };
+
+        if current != contents {
+            return Err(Box::new(WorkspaceError::StaleCodeowners(rel_path)));
+        }
+
+        println!("Code owners file {} is up to date", color::file(&rel_path));
+
+        return Ok(());
+    }
+
+    debug!(
+        target: TARGET,
+        "Writing code owners file to {}",
+        color::path(&codeowners_path)
+    );
+
+    fs::create_dir_all(codeowners_path.parent().unwrap()).await?;
+    fs::write(&codeowners_path, contents).await?;
+
+    println!("Generated code owners file {}", color::file(&rel_path));
+
+    Ok(())
+}
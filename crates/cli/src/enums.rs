@@ -3,13 +3,25 @@ use serde::{Deserialize, Serialize};
 use strum::Display;
 
 #[derive(ValueEnum, Clone, Debug, Default, Display)]
+#[strum(serialize_all = "kebab-case")]
 pub enum CacheMode {
     Off,
     Read,
+    ReadRemoteOnly,
     #[default]
     Write,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum GraphFormat {
+    #[default]
+    Dot,
+    Graphml,
+    Json,
+    Mermaid,
+}
+
 #[derive(ValueEnum, Clone, Debug, Default, Display)]
 pub enum LogLevel {
     Off,
@@ -6,16 +6,19 @@ pub mod queries;
 
 use crate::commands::bin::bin;
 use crate::commands::ci::{ci, CiOptions};
+use crate::commands::clean::{clean, CleanOptions};
 use crate::commands::dep_graph::dep_graph;
+use crate::commands::graph_server::GraphServerOptions;
 use crate::commands::init::{init, InitOptions};
 use crate::commands::project::project;
 use crate::commands::project_graph::project_graph;
 use crate::commands::query::{self, QueryProjectsOptions, QueryTouchedFilesOptions};
 use crate::commands::run::{run, RunOptions};
 use crate::commands::setup::setup;
+use crate::commands::sync;
 use crate::commands::teardown::teardown;
 use crate::helpers::setup_colors;
-use app::{App, Commands, QueryCommands};
+use app::{App, Commands, QueryCommands, SyncCommands};
 use clap::Parser;
 use console::Term;
 use enums::LogLevel;
@@ -51,10 +54,9 @@ pub async fn run_cli() {
 
     Logger::init(map_log_level(args.log), args.log_file);
 
-    // Setup caching
-    if env::var("MOON_CACHE").is_err() {
-        env::set_var("MOON_CACHE", args.cache.to_string().to_lowercase());
-    }
+    // Setup caching. The arg falls back to the env var when not passed,
+    // so always set it, otherwise `--cache` would be ignored when the env var exists.
+    env::set_var("MOON_CACHE", args.cache.to_string());
 
     // Match and run subcommand
     let result = match &args.command {
@@ -64,16 +66,48 @@ pub async fn run_cli() {
             head,
             job,
             job_total,
+            concurrency,
+            trace,
         } => {
             ci(CiOptions {
                 base: base.clone(),
+                concurrency: *concurrency,
                 head: head.clone(),
                 job: *job,
                 job_total: *job_total,
+                trace: *trace,
+            })
+            .await
+        }
+        Commands::Clean {
+            dry_run,
+            lifetime,
+            max_size,
+        } => {
+            clean(CleanOptions {
+                dry_run: *dry_run,
+                lifetime: *lifetime,
+                max_size: *max_size,
             })
             .await
         }
-        Commands::DepGraph { target } => dep_graph(target).await,
+        Commands::DepGraph {
+            target,
+            format,
+            serve,
+            host,
+            port,
+        } => {
+            dep_graph(
+                target,
+                *format,
+                serve.then(|| GraphServerOptions {
+                    host: host.clone(),
+                    port: *port,
+                }),
+            )
+            .await
+        }
         Commands::Init {
             dest,
             force,
@@ -93,12 +127,31 @@ pub async fn run_cli() {
             .await
         }
         Commands::Project { id, json } => project(id, *json).await,
-        Commands::ProjectGraph { id } => project_graph(id).await,
+        Commands::ProjectGraph {
+            id,
+            format,
+            serve,
+            host,
+            port,
+        } => {
+            project_graph(
+                id,
+                *format,
+                serve.then(|| GraphServerOptions {
+                    host: host.clone(),
+                    port: *port,
+                }),
+            )
+            .await
+        }
         Commands::Query { command } => match command {
+            QueryCommands::Hash { hash } => query::hash(hash).await,
+            QueryCommands::HashDiff { left, right } => query::hash_diff(left, right).await,
             QueryCommands::Projects {
                 id,
                 language,
                 source,
+                tags,
                 tasks,
                 type_of,
             } => {
@@ -106,6 +159,7 @@ pub async fn run_cli() {
                     id: id.clone(),
                     language: language.clone(),
                     source: source.clone(),
+                    tags: tags.clone(),
                     tasks: tasks.clone(),
                     type_of: type_of.clone(),
                 })
@@ -132,26 +186,59 @@ pub async fn run_cli() {
         Commands::Run {
             target,
             affected,
+            concurrency,
             dependents,
             status,
             passthrough,
             profile,
+            trace,
             upstream,
         } => {
             run(
-                target,
+                &[target.clone()],
                 RunOptions {
                     affected: *affected,
+                    concurrency: *concurrency,
                     dependents: *dependents,
                     status: *status,
                     passthrough: passthrough.clone(),
                     profile: profile.clone(),
+                    trace: *trace,
+                    upstream: *upstream,
+                },
+            )
+            .await
+        }
+        Commands::RunMany {
+            targets,
+            affected,
+            concurrency,
+            dependents,
+            status,
+            passthrough,
+            profile,
+            trace,
+            upstream,
+        } => {
+            run(
+                targets,
+                RunOptions {
+                    affected: *affected,
+                    concurrency: *concurrency,
+                    dependents: *dependents,
+                    status: *status,
+                    passthrough: passthrough.clone(),
+                    profile: profile.clone(),
+                    trace: *trace,
                     upstream: *upstream,
                 },
             )
             .await
         }
         Commands::Setup => setup().await,
+        Commands::Sync { command } => match command {
+            SyncCommands::Codeowners { check } => sync::codeowners(*check).await,
+        },
         Commands::Teardown => teardown().await,
     };
 
@@ -0,0 +1,133 @@
+use moon_logger::{color, debug};
+use moon_utils::fs;
+use moon_workspace::{Workspace, WorkspaceError};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+const TARGET: &str = "moon:query:hash";
+
+#[derive(Deserialize, Serialize)]
+pub struct QueryHashResult {
+    pub hash: String,
+    pub manifest: Value,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct QueryHashDifference {
+    pub field: String,
+    pub key: Option<String>,
+    pub left: Option<Value>,
+    pub right: Option<Value>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct QueryHashDiffResult {
+    pub left: String,
+    pub right: String,
+    pub differences: Vec<QueryHashDifference>,
+}
+
+/// Find the full hash in the cache that starts with the provided (partial) hash.
+async fn find_hash(workspace: &Workspace, hash: &str) -> Result<String, WorkspaceError> {
+    if workspace.cache.get_hash_path(hash).exists() {
+        return Ok(hash.to_owned());
+    }
+
+    let mut matches = workspace.cache.find_hashes(hash).await?;
+
+    match matches.len() {
+        0 => Err(WorkspaceError::MissingHashManifest(hash.to_owned())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(WorkspaceError::AmbiguousHashManifest(hash.to_owned())),
+    }
+}
+
+/// Load the manifest of a hash from the cache. Supports partial hashes.
+pub async fn query_hash(
+    workspace: &Workspace,
+    hash: &str,
+) -> Result<QueryHashResult, WorkspaceError> {
+    debug!(
+        target: TARGET,
+        "Querying for hash manifest {}",
+        color::symbol(hash)
+    );
+
+    let hash = find_hash(workspace, hash).await?;
+    let manifest: Value = fs::read_json(workspace.cache.get_hash_path(&hash)).await?;
+
+    Ok(QueryHashResult { hash, manifest })
+}
+
+/// Compare 2 hash manifests and return a list of all fields that differ.
+/// Object fields (input files, package dependencies, etc) are compared per key,
+/// while all other fields are compared by their entire value.
+pub fn diff_hash_manifests(left: &Value, right: &Value) -> Vec<QueryHashDifference> {
+    let mut differences = vec![];
+    let empty = serde_json::Map::new();
+    let left_fields = left.as_object().unwrap_or(&empty);
+    let right_fields = right.as_object().unwrap_or(&empty);
+    let fields: BTreeSet<&String> = left_fields.keys().chain(right_fields.keys()).collect();
+
+    for field in fields {
+        let left_value = left_fields.get(field);
+        let right_value = right_fields.get(field);
+
+        if left_value == right_value {
+            continue;
+        }
+
+        if let (Some(Value::Object(left_map)), Some(Value::Object(right_map))) =
+            (left_value, right_value)
+        {
+            let keys: BTreeSet<&String> = left_map.keys().chain(right_map.keys()).collect();
+
+            for key in keys {
+                let left_item = left_map.get(key);
+                let right_item = right_map.get(key);
+
+                if left_item != right_item {
+                    differences.push(QueryHashDifference {
+                        field: field.to_owned(),
+                        key: Some(key.to_owned()),
+                        left: left_item.cloned(),
+                        right: right_item.cloned(),
+                    });
+                }
+            }
+        } else {
+            differences.push(QueryHashDifference {
+                field: field.to_owned(),
+                key: None,
+                left: left_value.cloned(),
+                right: right_value.cloned(),
+            });
+        }
+    }
+
+    differences
+}
+
+/// Load the manifests of 2 hashes from the cache, and diff their contents.
+pub async fn query_hash_diff(
+    workspace: &Workspace,
+    left: &str,
+    right: &str,
+) -> Result<QueryHashDiffResult, WorkspaceError> {
+    let left = query_hash(workspace, left).await?;
+    let right = query_hash(workspace, right).await?;
+
+    debug!(
+        target: TARGET,
+        "Diffing hash manifests {} and {}",
+        color::symbol(&left.hash),
+        color::symbol(&right.hash)
+    );
+
+    Ok(QueryHashDiffResult {
+        differences: diff_hash_manifests(&left.manifest, &right.manifest),
+        left: left.hash,
+        right: right.hash,
+    })
+}
@@ -1,2 +1,3 @@
+pub mod hash;
 pub mod projects;
 pub mod touched_files;
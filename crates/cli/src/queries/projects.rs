@@ -11,6 +11,7 @@ pub struct QueryProjectsOptions {
     pub id: Option<String>,
     pub language: Option<String>,
     pub source: Option<String>,
+    pub tags: Option<String>,
     pub tasks: Option<String>,
     pub type_of: Option<String>,
 }
@@ -51,6 +52,7 @@ pub async fn query_projects(
     let id_regex = convert_to_regex("id", &options.id)?;
     let language_regex = convert_to_regex("language", &options.language)?;
     let source_regex = convert_to_regex("source", &options.source)?;
+    let tags_regex = convert_to_regex("tags", &options.tags)?;
     let tasks_regex = convert_to_regex("tasks", &options.tasks)?;
     let type_regex = convert_to_regex("type", &options.type_of)?;
 
@@ -69,6 +71,14 @@ pub async fn query_projects(
             }
         }
 
+        if let Some(regex) = &tags_regex {
+            let has_tag = project.config.tags.iter().any(|tag| regex.is_match(tag));
+
+            if !has_tag {
+                continue;
+            }
+        }
+
         if let Some(regex) = &tasks_regex {
             let has_task = project.tasks.keys().any(|task_id| regex.is_match(task_id));
 
@@ -0,0 +1,113 @@
+use moon_cache::CacheEngine;
+use moon_utils::test::{create_fixtures_sandbox, create_moon_command_in, get_assert_output};
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn reports_stale_hashes_on_dry_run() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("system:echo")
+        .assert();
+
+    let engine = CacheEngine::create(fixture.path()).await.unwrap();
+    let hash = engine
+        .cache_run_target_state("system:echo")
+        .await
+        .unwrap()
+        .item
+        .hash;
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--lifetime")
+        .arg("0s")
+        .arg("--dryRun")
+        .assert();
+
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains(&hash).eval(&output));
+    assert!(predicate::str::contains("Would clean 1 hashes").eval(&output));
+    assert!(engine.get_hash_path(&hash).exists());
+}
+
+#[tokio::test]
+async fn deletes_stale_hashes() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("system:echo")
+        .assert();
+
+    let engine = CacheEngine::create(fixture.path()).await.unwrap();
+    let hash = engine
+        .cache_run_target_state("system:echo")
+        .await
+        .unwrap()
+        .item
+        .hash;
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--lifetime")
+        .arg("0s")
+        .assert();
+
+    assert!(predicate::str::contains("Cleaned 1 hashes").eval(&get_assert_output(&assert)));
+    assert!(!engine.get_hash_path(&hash).exists());
+}
+
+#[test]
+fn keeps_recent_hashes() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    create_moon_command_in(fixture.path())
+        .arg("run")
+        .arg("system:echo")
+        .assert();
+
+    let assert = create_moon_command_in(fixture.path()).arg("clean").assert();
+
+    assert!(predicate::str::contains("Cleaned 0 hashes").eval(&get_assert_output(&assert)));
+}
+
+#[test]
+fn errors_for_invalid_lifetime() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--lifetime")
+        .arg("forever")
+        .assert();
+
+    assert.failure();
+}
+
+#[test]
+fn errors_for_overflowing_values() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--maxSize")
+        .arg("99999999999999")
+        .assert()
+        .failure();
+
+    assert!(
+        predicate::str::contains("Must be a size in megabytes").eval(&get_assert_output(&assert))
+    );
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("clean")
+        .arg("--lifetime")
+        .arg("999999999999999d")
+        .assert()
+        .failure();
+
+    assert!(predicate::str::contains("Must be a duration").eval(&get_assert_output(&assert)));
+}
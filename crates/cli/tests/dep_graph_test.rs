@@ -39,3 +39,41 @@ fn includes_dependents_when_focused() {
 
     assert_snapshot!(get_assert_output(&assert));
 }
+
+mod format {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let assert = create_moon_command("cases")
+            .arg("dep-graph")
+            .arg("node:standard")
+            .arg("--format")
+            .arg("json")
+            .assert();
+
+        let json: serde_json::Value = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+        let run = json["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|node| node["label"] == "RunTarget(node:standard)")
+            .unwrap();
+
+        assert_eq!(json["version"], 1);
+        assert_eq!(run["type"], "runTarget");
+        assert_eq!(run["task"]["command"], "node");
+    }
+
+    #[test]
+    fn mermaid() {
+        let assert = create_moon_command("cases")
+            .arg("dep-graph")
+            .arg("node:standard")
+            .arg("--format")
+            .arg("mermaid")
+            .assert();
+
+        assert_snapshot!(get_assert_output(&assert));
+    }
+}
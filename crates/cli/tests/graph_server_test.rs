@@ -0,0 +1,117 @@
+use assert_cmd::cargo::CommandCargoExt;
+use moon_utils::test::get_fixtures_dir;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+struct GraphServer {
+    address: String,
+    child: Child,
+}
+
+impl Drop for GraphServer {
+    fn drop(&mut self) {
+        self.child.kill().unwrap();
+        self.child.wait().unwrap();
+    }
+}
+
+fn start_server(command: &str) -> GraphServer {
+    let mut child = Command::cargo_bin("moon")
+        .unwrap()
+        .current_dir(get_fixtures_dir("projects"))
+        .args([command, "--serve"])
+        .env("MOON_CACHE", "off")
+        .env("MOON_TEST", "true")
+        .env("NO_COLOR", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    // Wait for the server to print its address
+    let mut line = String::new();
+
+    BufReader::new(child.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+
+    let address = line
+        .split("http://")
+        .nth(1)
+        .unwrap()
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_owned();
+
+    GraphServer { address, child }
+}
+
+fn request(server: &GraphServer, method: &str, path: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(&server.address).unwrap();
+
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        method, path, server.address
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+
+    (status, body.to_owned())
+}
+
+#[test]
+fn serves_explorer() {
+    let server = start_server("project-graph");
+    let (status, body) = request(&server, "GET", "/");
+
+    assert_eq!(status, 200);
+    assert!(body.contains("moon graph explorer"));
+}
+
+#[test]
+fn serves_project_graph() {
+    let server = start_server("project-graph");
+    let (status, body) = request(&server, "GET", "/api/project-graph");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(status, 200);
+    assert!(json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|node| node["id"] == "foo"));
+    assert!(json["edges"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({ "source": "foo", "target": "bar", "type": "dependsOn" })));
+}
+
+#[test]
+fn serves_dep_graph() {
+    let server = start_server("dep-graph");
+    let (status, body) = request(&server, "GET", "/api/dep-graph");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+
+    assert_eq!(status, 200);
+    assert!(json["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|node| node["type"] == "runTarget"));
+}
+
+#[test]
+fn returns_not_found() {
+    let server = start_server("project-graph");
+
+    assert_eq!(request(&server, "GET", "/unknown").0, 404);
+    assert_eq!(request(&server, "POST", "/api/project-graph").0, 405);
+}
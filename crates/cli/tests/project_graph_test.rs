@@ -36,3 +36,50 @@ fn single_project_no_dependencies() {
 
     assert_snapshot!(get_assert_output(&assert));
 }
+
+mod format {
+    use super::*;
+
+    #[test]
+    fn json() {
+        let assert = create_moon_command("projects")
+            .arg("project-graph")
+            .arg("foo")
+            .arg("--format")
+            .arg("json")
+            .assert();
+
+        let json: serde_json::Value = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+
+        assert_eq!(json["version"], 1);
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            json["edges"][0],
+            serde_json::json!({ "source": "foo", "target": "bar", "type": "dependsOn" })
+        );
+    }
+
+    #[test]
+    fn mermaid() {
+        let assert = create_moon_command("projects")
+            .arg("project-graph")
+            .arg("foo")
+            .arg("--format")
+            .arg("mermaid")
+            .assert();
+
+        assert_snapshot!(get_assert_output(&assert));
+    }
+
+    #[test]
+    fn graphml() {
+        let assert = create_moon_command("projects")
+            .arg("project-graph")
+            .arg("foo")
+            .arg("--format")
+            .arg("graphml")
+            .assert();
+
+        assert_snapshot!(get_assert_output(&assert));
+    }
+}
@@ -1,10 +1,192 @@
 use moon_cli::enums::TouchedStatus;
+use moon_cli::queries::hash::{QueryHashDiffResult, QueryHashDifference, QueryHashResult};
 use moon_cli::queries::projects::QueryProjectsResult;
 use moon_cli::queries::touched_files::QueryTouchedFilesResult;
 use moon_utils::string_vec;
 use moon_utils::test::{
     create_fixtures_sandbox, create_moon_command_in, get_assert_output, run_git_command,
 };
+use predicates::prelude::*;
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+fn write_hash_manifest(root: &Path, hash: &str, manifest: serde_json::Value) {
+    let dir = root.join(".moon/cache/hashes");
+
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join(format!("{}.json", hash)), manifest.to_string()).unwrap();
+}
+
+mod hash {
+    use super::*;
+
+    #[test]
+    fn returns_manifest() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_hash_manifest(
+            fixture.path(),
+            "abc123",
+            json!({ "command": "tsc", "args": ["--build"] }),
+        );
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash")
+            .arg("abc123")
+            .assert();
+
+        let json: QueryHashResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+
+        assert_eq!(json.hash, "abc123");
+        assert_eq!(
+            json.manifest,
+            json!({ "command": "tsc", "args": ["--build"] })
+        );
+    }
+
+    #[test]
+    fn supports_partial_hashes() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_hash_manifest(fixture.path(), "abc123", json!({}));
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash")
+            .arg("abc")
+            .assert();
+
+        let json: QueryHashResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+
+        assert_eq!(json.hash, "abc123");
+    }
+
+    #[test]
+    fn errors_for_ambiguous_hash() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_hash_manifest(fixture.path(), "abc123", json!({}));
+        write_hash_manifest(fixture.path(), "abc456", json!({}));
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash")
+            .arg("abc")
+            .assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "Multiple hash manifests found for abc",
+        ));
+    }
+
+    #[test]
+    fn errors_for_missing_hash() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash")
+            .arg("abc123")
+            .assert();
+
+        assert.failure().stderr(predicate::str::contains(
+            "Unable to find a hash manifest for abc123",
+        ));
+    }
+}
+
+mod hash_diff {
+    use super::*;
+
+    #[test]
+    fn returns_differences() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_hash_manifest(
+            fixture.path(),
+            "abc123",
+            json!({
+                "args": ["--build"],
+                "command": "tsc",
+                "inputHashes": { "a.ts": "111", "b.ts": "222" },
+                "packageDependencies": { "react": "17.0.0" },
+            }),
+        );
+
+        write_hash_manifest(
+            fixture.path(),
+            "def456",
+            json!({
+                "args": ["--build", "--force"],
+                "command": "tsc",
+                "inputHashes": { "a.ts": "111", "b.ts": "333", "c.ts": "444" },
+                "packageDependencies": {},
+            }),
+        );
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash-diff")
+            .arg("abc")
+            .arg("def")
+            .assert();
+
+        let json: QueryHashDiffResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+
+        assert_eq!(json.left, "abc123");
+        assert_eq!(json.right, "def456");
+        assert_eq!(
+            json.differences,
+            vec![
+                QueryHashDifference {
+                    field: "args".into(),
+                    key: None,
+                    left: Some(json!(["--build"])),
+                    right: Some(json!(["--build", "--force"])),
+                },
+                QueryHashDifference {
+                    field: "inputHashes".into(),
+                    key: Some("b.ts".into()),
+                    left: Some(json!("222")),
+                    right: Some(json!("333")),
+                },
+                QueryHashDifference {
+                    field: "inputHashes".into(),
+                    key: Some("c.ts".into()),
+                    left: None,
+                    right: Some(json!("444")),
+                },
+                QueryHashDifference {
+                    field: "packageDependencies".into(),
+                    key: Some("react".into()),
+                    left: Some(json!("17.0.0")),
+                    right: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_no_differences_for_same_contents() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        write_hash_manifest(fixture.path(), "abc123", json!({ "command": "tsc" }));
+        write_hash_manifest(fixture.path(), "def456", json!({ "command": "tsc" }));
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("hash-diff")
+            .arg("abc123")
+            .arg("def456")
+            .assert();
+
+        let json: QueryHashDiffResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+
+        assert!(json.differences.is_empty());
+    }
+}
 
 mod projects {
     use super::*;
@@ -73,6 +255,39 @@ mod projects {
         assert_eq!(json.options.source.unwrap(), "config$".to_string());
     }
 
+    #[test]
+    fn can_filter_by_tags() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("projects")
+            .args(["--tags", "frontend"])
+            .assert();
+
+        let json: QueryProjectsResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+        let ids: Vec<String> = json.projects.iter().map(|p| p.id.clone()).collect();
+
+        assert_eq!(ids, string_vec!["js", "ts"]);
+        assert_eq!(json.options.tags.unwrap(), "frontend".to_string());
+    }
+
+    #[test]
+    fn can_filter_by_namespaced_tags() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("query")
+            .arg("projects")
+            .args(["--tags", "^scope:"])
+            .assert();
+
+        let json: QueryProjectsResult = serde_json::from_str(&get_assert_output(&assert)).unwrap();
+        let ids: Vec<String> = json.projects.iter().map(|p| p.id.clone()).collect();
+
+        assert_eq!(ids, string_vec!["ts"]);
+    }
+
     #[test]
     fn can_filter_by_tasks() {
         let fixture = create_fixtures_sandbox("projects");
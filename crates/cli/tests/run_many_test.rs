@@ -0,0 +1,130 @@
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command, create_moon_command_in, get_assert_output,
+};
+use predicates::prelude::*;
+
+#[test]
+fn requires_a_target() {
+    let assert = create_moon_command("cases").arg("run-many").assert();
+
+    assert.failure();
+}
+
+#[test]
+fn errors_for_unknown_project() {
+    let assert = create_moon_command("cases")
+        .arg("run-many")
+        .arg("system:echo")
+        .arg("unknown:test")
+        .assert();
+
+    assert!(
+        predicate::str::contains("No project has been configured with the ID")
+            .eval(&get_assert_output(&assert))
+    );
+}
+
+#[test]
+fn errors_for_self_scope_outside_of_project() {
+    let assert = create_moon_command("cases")
+        .arg("run-many")
+        .arg("~:test")
+        .assert();
+
+    assert!(predicate::str::contains("Project self scope (~:)").eval(&get_assert_output(&assert)));
+}
+
+#[test]
+fn runs_multiple_targets() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("run-many")
+        .arg("targetScopeB:selfOther")
+        .arg("targetScopeC:all")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("targetScopeB:selfOther").eval(&output));
+    assert!(predicate::str::contains("targetScopeC:all").eval(&output));
+    assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+}
+
+#[test]
+fn runs_shared_dependencies_once() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("run-many")
+        .arg("targetScopeA:deps")
+        .arg("depsA:standard")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("targetScopeA:deps").eval(&output));
+    assert!(predicate::str::contains("Tasks: 4 completed").eval(&output));
+}
+
+#[test]
+fn supports_all_scope() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("run-many")
+        .arg(":all")
+        .arg("targetScopeB:self")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("targetScopeA:all").eval(&output));
+    assert!(predicate::str::contains("targetScopeB:all").eval(&output));
+    assert!(predicate::str::contains("targetScopeC:all").eval(&output));
+    assert!(predicate::str::contains("targetScopeB:self").eval(&output));
+    assert!(predicate::str::contains("Tasks: 5 completed").eval(&output));
+}
+
+#[test]
+fn supports_deps_scope_from_project() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path().join("target-scope-a"))
+        .arg("run-many")
+        .arg("^:standard")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("depsA:standard").eval(&output));
+    assert!(predicate::str::contains("depsB:standard").eval(&output));
+    assert!(predicate::str::contains("depsC:standard").eval(&output));
+    assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+}
+
+#[test]
+fn supports_self_scope_from_project() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path().join("target-scope-b"))
+        .arg("run-many")
+        .arg("~:self")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("targetScopeB:self").eval(&output));
+    assert!(predicate::str::contains("targetScopeB:selfOther").eval(&output));
+    assert!(predicate::str::contains("Tasks: 2 completed").eval(&output));
+}
+
+#[test]
+fn runs_with_bounded_concurrency() {
+    let fixture = create_fixtures_sandbox("cases");
+
+    let assert = create_moon_command_in(fixture.path())
+        .arg("run-many")
+        .arg("--concurrency")
+        .arg("1")
+        .arg(":all")
+        .assert();
+    let output = get_assert_output(&assert);
+
+    assert!(predicate::str::contains("Tasks: 3 completed").eval(&output));
+}
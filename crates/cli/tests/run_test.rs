@@ -200,6 +200,62 @@ mod caching {
         assert_snapshot!(get_assert_output(&assert));
     }
 
+    #[test]
+    fn doesnt_use_cache_when_off() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("node:standard")
+            .assert();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("--cache")
+            .arg("off")
+            .arg("run")
+            .arg("node:standard")
+            .assert();
+
+        assert!(!predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+    }
+
+    #[test]
+    fn cache_arg_overrides_env_var() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("node:standard")
+            .assert();
+
+        let assert = create_moon_command_in(fixture.path())
+            .env("MOON_CACHE", "write")
+            .arg("--cache")
+            .arg("off")
+            .arg("run")
+            .arg("node:standard")
+            .assert();
+
+        assert!(!predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+    }
+
+    #[test]
+    fn doesnt_create_hashes_when_read() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("--cache")
+            .arg("read")
+            .arg("run")
+            .arg("node:standard")
+            .assert();
+
+        assert!(!fixture
+            .path()
+            .join(".moon/cache/runs/node/standard/lastRunState.json")
+            .exists());
+    }
+
     #[test]
     fn creates_runfile() {
         let fixture = create_fixtures_sandbox("cases");
@@ -253,6 +309,123 @@ mod caching {
     }
 }
 
+mod run_report {
+    use super::*;
+
+    #[test]
+    fn creates_run_report() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .assert();
+
+        let report: serde_json::Value = serde_json::from_str(
+            &read_to_string(fixture.path().join(".moon/cache/runReport.json")).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(report["primaryTargets"], serde_json::json!(["system:echo"]));
+
+        let action = report["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|action| action["label"] == "RunTarget(system:echo)")
+            .unwrap();
+
+        assert_eq!(action["status"], "passed");
+        assert_eq!(action["cached"], false);
+        assert_eq!(action["attempts"].as_array().unwrap().len(), 1);
+        assert!(action["hash"].is_string());
+    }
+
+    #[test]
+    fn marks_cache_hits() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .assert();
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .assert();
+
+        let report: serde_json::Value = serde_json::from_str(
+            &read_to_string(fixture.path().join(".moon/cache/runReport.json")).unwrap(),
+        )
+        .unwrap();
+
+        let action = report["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|action| action["label"] == "RunTarget(system:echo)")
+            .unwrap();
+
+        assert_eq!(action["status"], "cached");
+        assert_eq!(action["cached"], true);
+    }
+}
+
+mod run_trace {
+    use super::*;
+
+    #[test]
+    fn doesnt_create_by_default() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .assert();
+
+        assert!(!fixture.path().join(".moon/cache/runTrace.json").exists());
+    }
+
+    #[test]
+    fn creates_trace_events() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("system:echo")
+            .arg("--trace")
+            .assert();
+
+        let trace: serde_json::Value = serde_json::from_str(
+            &read_to_string(fixture.path().join(".moon/cache/runTrace.json")).unwrap(),
+        )
+        .unwrap();
+
+        let events = trace["traceEvents"].as_array().unwrap();
+
+        assert!(events
+            .iter()
+            .any(|event| event["ph"] == "M" && event["name"] == "thread_name"));
+
+        let setup = events
+            .iter()
+            .find(|event| event["name"] == "SetupToolchain")
+            .unwrap();
+        let target = events
+            .iter()
+            .find(|event| event["name"] == "RunTarget(system:echo)")
+            .unwrap();
+
+        assert_eq!(target["ph"], "X");
+        assert_eq!(target["cat"], "RunTarget");
+        assert_eq!(target["args"]["status"], "passed");
+        assert!(target["args"]["worker"].is_u64());
+        assert!(target["args"]["batch"].as_u64() > setup["args"]["batch"].as_u64());
+        assert!(target["ts"].as_u64() >= setup["ts"].as_u64());
+    }
+}
+
 mod dependencies {
     use super::*;
 
@@ -630,21 +803,24 @@ mod system_windows {
 mod outputs {
     use super::*;
 
-    // fn debug_dir(dir: &Path) {
-    //     for entry in std::fs::read_dir(dir).unwrap() {
-    //         let entry = entry.unwrap();
-    //         let path = entry.path();
+    async fn extract_archived_files(fixture: &Path, hash: &str) -> Vec<String> {
+        let engine = CacheEngine::create(fixture).await.unwrap();
+        let outputs = engine.cache_hash_outputs_state(hash).await.unwrap();
 
-    //         if path.is_dir() {
-    //             debug_dir(&path);
-    //         } else {
-    //             println!("{:#?}", path);
-    //         }
-    //     }
-    // }
+        outputs
+            .item
+            .files
+            .into_iter()
+            .map(|file| {
+                assert!(engine.get_blob_path(&file.digest).exists());
+
+                file.path
+            })
+            .collect()
+    }
 
     #[tokio::test]
-    async fn links_single_file() {
+    async fn archives_single_file() {
         let fixture = create_fixtures_sandbox("cases");
 
         create_moon_command_in(fixture.path())
@@ -661,16 +837,13 @@ mod outputs {
             .join(format!("{}.json", hash))
             .exists());
         // outputs
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(hash)
-            .join("lib/one.js")
-            .exists());
+        let files = extract_archived_files(fixture.path(), &hash).await;
+
+        assert!(files.contains(&"lib/one.js".to_owned()));
     }
 
     #[tokio::test]
-    async fn links_multiple_files() {
+    async fn archives_multiple_files() {
         let fixture = create_fixtures_sandbox("cases");
 
         create_moon_command_in(fixture.path())
@@ -687,22 +860,14 @@ mod outputs {
             .join(format!("{}.json", hash))
             .exists());
         // outputs
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/one.js")
-            .exists());
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/two.js")
-            .exists());
+        let files = extract_archived_files(fixture.path(), &hash).await;
+
+        assert!(files.contains(&"lib/one.js".to_owned()));
+        assert!(files.contains(&"lib/two.js".to_owned()));
     }
 
     #[tokio::test]
-    async fn links_single_folder() {
+    async fn archives_single_folder() {
         let fixture = create_fixtures_sandbox("cases");
 
         create_moon_command_in(fixture.path())
@@ -719,22 +884,14 @@ mod outputs {
             .join(format!("{}.json", hash))
             .exists());
         // outputs
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/one.js")
-            .exists());
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/two.js")
-            .exists());
+        let files = extract_archived_files(fixture.path(), &hash).await;
+
+        assert!(files.contains(&"lib/one.js".to_owned()));
+        assert!(files.contains(&"lib/two.js".to_owned()));
     }
 
     #[tokio::test]
-    async fn links_multiple_folders() {
+    async fn archives_multiple_folders() {
         let fixture = create_fixtures_sandbox("cases");
 
         create_moon_command_in(fixture.path())
@@ -751,22 +908,14 @@ mod outputs {
             .join(format!("{}.json", hash))
             .exists());
         // outputs
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/one.js")
-            .exists());
-        assert!(fixture
-            .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("esm/two.js")
-            .exists());
+        let files = extract_archived_files(fixture.path(), &hash).await;
+
+        assert!(files.contains(&"lib/one.js".to_owned()));
+        assert!(files.contains(&"esm/two.js".to_owned()));
     }
 
     #[tokio::test]
-    async fn links_both_file_and_folder() {
+    async fn archives_both_file_and_folder() {
         let fixture = create_fixtures_sandbox("cases");
 
         create_moon_command_in(fixture.path())
@@ -783,17 +932,149 @@ mod outputs {
             .join(format!("{}.json", hash))
             .exists());
         // outputs
+        let files = extract_archived_files(fixture.path(), &hash).await;
+
+        assert!(files.contains(&"lib/one.js".to_owned()));
+        assert!(files.contains(&"esm/two.js".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn hydrates_missing_outputs_on_cache_hit() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFileAndFolder")
+            .assert();
+
+        std::fs::remove_file(fixture.path().join("outputs/lib/one.js")).unwrap();
+        std::fs::remove_dir_all(fixture.path().join("outputs/esm")).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFileAndFolder")
+            .assert();
+
+        assert!(predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+        assert!(fixture.path().join("outputs/lib/one.js").exists());
+        assert!(fixture.path().join("outputs/esm/two.js").exists());
+    }
+
+    #[tokio::test]
+    async fn reruns_when_outputs_missing_from_cache() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFolder")
+            .assert();
+
+        let hash = extract_hash_from_run(fixture.path(), "outputs:generateFolder").await;
+
+        std::fs::remove_dir_all(fixture.path().join(".moon/cache/blobs")).unwrap();
+        std::fs::create_dir_all(fixture.path().join(".moon/cache/blobs")).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFolder")
+            .assert();
+
+        assert!(!predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+        assert!(extract_archived_files(fixture.path(), &hash)
+            .await
+            .contains(&"lib/one.js".to_owned()));
+    }
+}
+
+mod remote_cache {
+    use super::*;
+    use std::fs;
+
+    fn append_remote_cache_config(root: &Path) {
+        let config_path = root.join(".moon/workspace.yml");
+        let config = read_to_string(&config_path).unwrap();
+
+        fs::write(
+            &config_path,
+            format!("{}\nremoteCache:\n  location: '.remote-cache'\n", config),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn uploads_hash_and_outputs() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        append_remote_cache_config(fixture.path());
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        let hash = extract_hash_from_run(fixture.path(), "outputs:generateFile").await;
+        let remote_dir = fixture.path().join(".remote-cache");
+
+        assert!(remote_dir.join(format!("{}.json", hash)).exists());
+        assert!(remote_dir.join(format!("{}.tar.zst", hash)).exists());
+    }
+
+    #[tokio::test]
+    async fn downloads_hash_and_outputs_on_local_miss() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        append_remote_cache_config(fixture.path());
+
+        create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        let hash = extract_hash_from_run(fixture.path(), "outputs:generateFile").await;
+
+        // Simulate a fresh machine
+        fs::remove_dir_all(fixture.path().join(".moon/cache")).unwrap();
+        fs::remove_dir_all(fixture.path().join("outputs/lib")).unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        assert!(predicate::str::contains("(cached)").eval(&get_assert_output(&assert)));
+        assert!(fixture.path().join("outputs/lib/one.js").exists());
         assert!(fixture
             .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("lib/one.js")
+            .join(".moon/cache/hashes")
+            .join(format!("{}.json", hash))
             .exists());
+    }
+
+    #[tokio::test]
+    async fn doesnt_upload_when_read_remote_only() {
+        let fixture = create_fixtures_sandbox("cases");
+
+        append_remote_cache_config(fixture.path());
+
+        create_moon_command_in(fixture.path())
+            .arg("--cache")
+            .arg("read-remote-only")
+            .arg("run")
+            .arg("outputs:generateFile")
+            .assert();
+
+        let hash = extract_hash_from_run(fixture.path(), "outputs:generateFile").await;
+
+        // Written to the local cache, but never published
         assert!(fixture
             .path()
-            .join(".moon/cache/out")
-            .join(&hash)
-            .join("esm/two.js")
+            .join(".moon/cache/hashes")
+            .join(format!("{}.json", hash))
+            .exists());
+        assert!(!fixture
+            .path()
+            .join(".remote-cache")
+            .join(format!("{}.json", hash))
             .exists());
     }
 }
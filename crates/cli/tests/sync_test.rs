@@ -0,0 +1,103 @@
+use insta::assert_snapshot;
+use moon_utils::test::{
+    create_fixtures_sandbox, create_moon_command_in, get_assert_output,
+    get_assert_stderr_output_clean,
+};
+use predicates::prelude::*;
+use std::fs;
+
+mod codeowners {
+    use super::*;
+
+    #[test]
+    fn generates_from_project_metadata() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .assert();
+
+        assert.success();
+
+        assert_snapshot!(fs::read_to_string(fixture.path().join(".github/CODEOWNERS")).unwrap());
+    }
+
+    #[test]
+    fn writes_to_existing_location() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        fs::write(fixture.path().join("CODEOWNERS"), "").unwrap();
+
+        create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .assert()
+            .success();
+
+        assert!(!fixture.path().join(".github/CODEOWNERS").exists());
+        assert!(predicate::str::contains("/langs/ts/ @moonrepo/frontend")
+            .eval(&fs::read_to_string(fixture.path().join("CODEOWNERS")).unwrap()));
+    }
+
+    #[test]
+    fn passes_check_when_up_to_date() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .assert();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .arg("--check")
+            .assert();
+
+        assert!(predicate::str::contains("is up to date").eval(&get_assert_output(&assert)));
+
+        assert.success();
+    }
+
+    #[test]
+    fn fails_check_when_stale() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        fs::create_dir_all(fixture.path().join(".github")).unwrap();
+        fs::write(fixture.path().join(".github/CODEOWNERS"), "* @someone\n").unwrap();
+
+        let assert = create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .arg("--check")
+            .assert();
+
+        assert!(
+            predicate::str::contains("Code owners file .github/CODEOWNERS is out of date.")
+                .eval(&get_assert_stderr_output_clean(&assert))
+        );
+
+        assert.failure();
+
+        // Check mode should never write
+        assert_eq!(
+            fs::read_to_string(fixture.path().join(".github/CODEOWNERS")).unwrap(),
+            "* @someone\n"
+        );
+    }
+
+    #[test]
+    fn fails_check_when_missing() {
+        let fixture = create_fixtures_sandbox("projects");
+
+        create_moon_command_in(fixture.path())
+            .arg("sync")
+            .arg("codeowners")
+            .arg("--check")
+            .assert()
+            .failure();
+
+        assert!(!fixture.path().join(".github/CODEOWNERS").exists());
+    }
+}
@@ -297,9 +297,11 @@ fileGroups:
                             merge_env: None,
                             merge_inputs: None,
                             merge_outputs: None,
+                            persistent: None,
                             retry_count: None,
                             run_in_ci: Some(false),
                             run_from_workspace_root: None,
+                            timeout: None,
                         },
                         ..TaskConfig::default()
                     },
@@ -5,7 +5,7 @@ pub mod task;
 
 use crate::errors::{create_validation_error, map_validation_errors_to_figment_errors};
 use crate::types::{FileGroups, ProjectID, TaskID};
-use crate::validators::validate_id;
+use crate::validators::{validate_id, validate_tag};
 use figment::{
     providers::{Format, Serialized, Yaml},
     Error as FigmentError, Figment,
@@ -26,6 +26,14 @@ fn validate_file_groups(map: &FileGroups) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_tags(list: &[String]) -> Result<(), ValidationError> {
+    for (index, tag) in list.iter().enumerate() {
+        validate_tag(&format!("tags[{}]", index), tag)?;
+    }
+
+    Ok(())
+}
+
 fn validate_tasks(map: &HashMap<String, TaskConfig>) -> Result<(), ValidationError> {
     for (name, task) in map {
         validate_id(&format!("tasks.{}", name), name)?;
@@ -115,10 +123,21 @@ pub struct ProjectWorkspaceInheritedTasksConfig {
     pub rename: Option<HashMap<TaskID, TaskID>>,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+pub struct ProjectWorkspaceInferredTasksConfig {
+    pub exclude: Option<Vec<String>>,
+
+    pub include: Option<Vec<String>>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectWorkspaceConfig {
+    #[validate]
+    pub inferred_tasks: ProjectWorkspaceInferredTasksConfig,
+
     #[validate]
     pub inherited_tasks: ProjectWorkspaceInheritedTasksConfig,
 }
@@ -138,6 +157,9 @@ pub struct ProjectConfig {
     #[validate]
     pub project: Option<ProjectMetadataConfig>,
 
+    #[validate(custom = "validate_tags")]
+    pub tags: Vec<String>,
+
     #[validate(custom = "validate_tasks")]
     #[validate]
     pub tasks: HashMap<String, TaskConfig>,
@@ -570,6 +592,56 @@ project:
         }
     }
 
+    mod tags {
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found unsigned int `123`, expected a sequence for key \"project.tags\""
+        )]
+        fn invalid_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(super::constants::CONFIG_PROJECT_FILENAME, "tags: 123")?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid tag")]
+        fn invalid_tag() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    "tags: ['frontend', '#invalid']",
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn supports_namespaces() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    "tags: ['frontend', 'scope:billing']",
+                )?;
+
+                let config = super::load_jailed_config()?;
+
+                assert_eq!(
+                    config.tags,
+                    vec![String::from("frontend"), String::from("scope:billing")]
+                );
+
+                Ok(())
+            });
+        }
+    }
+
     mod workspace {
         #[test]
         #[should_panic(
@@ -623,5 +695,25 @@ workspace:
                 Ok(())
             });
         }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected a sequence for key \"project.workspace.inferredTasks.exclude\""
+        )]
+        fn invalid_inferred_tasks_value_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_PROJECT_FILENAME,
+                    r#"
+workspace:
+    inferredTasks:
+        exclude: abc"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
     }
 }
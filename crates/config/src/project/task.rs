@@ -1,3 +1,4 @@
+use crate::errors::create_validation_error;
 use crate::types::{FilePath, FilePathOrGlob, TargetID};
 use crate::validators::{validate_child_or_root_path, validate_target};
 use moon_utils::process::split_args;
@@ -38,6 +39,18 @@ fn validate_outputs(list: &[String]) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_timeout(value: u64) -> Result<(), ValidationError> {
+    if value == 0 {
+        return Err(create_validation_error(
+            "invalid_timeout",
+            "options.timeout",
+            String::from("Must be greater than 0"),
+        ));
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Display, JsonSchema, PartialEq, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskType {
@@ -71,12 +84,17 @@ pub struct TaskOptionsConfig {
 
     pub merge_outputs: Option<TaskMergeStrategy>,
 
+    pub persistent: Option<bool>,
+
     pub retry_count: Option<u8>,
 
     #[serde(rename = "runInCI")]
     pub run_in_ci: Option<bool>,
 
     pub run_from_workspace_root: Option<bool>,
+
+    #[validate(custom = "validate_timeout")]
+    pub timeout: Option<u64>,
 }
 
 impl Default for TaskOptionsConfig {
@@ -87,9 +105,11 @@ impl Default for TaskOptionsConfig {
             merge_env: Some(TaskMergeStrategy::default()),
             merge_inputs: Some(TaskMergeStrategy::default()),
             merge_outputs: Some(TaskMergeStrategy::default()),
+            persistent: Some(false),
             retry_count: Some(0),
             run_in_ci: Some(true),
             run_from_workspace_root: Some(false),
+            timeout: None,
         }
     }
 }
@@ -613,5 +633,26 @@ options:
                 Ok(())
             });
         }
+
+        #[test]
+        #[should_panic(
+            expected = "invalid type: found string \"abc\", expected u64 for key \"default.options.timeout\""
+        )]
+        fn invalid_timeout_type() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::CONFIG_FILENAME,
+                    r#"
+command: foo
+options:
+    timeout: abc
+"#,
+                )?;
+
+                super::load_jailed_config()?;
+
+                Ok(())
+            });
+        }
     }
 }
@@ -1,5 +1,5 @@
 use crate::errors::create_validation_error;
-use moon_utils::regex::{matches_id, matches_target};
+use moon_utils::regex::{matches_id, matches_tag, matches_target};
 use semver::Version;
 use std::path::Path;
 use validator::{validate_url as validate_base_url, ValidationError};
@@ -74,6 +74,20 @@ pub fn validate_id(key: &str, id: &str) -> Result<(), ValidationError> {
     Ok(())
 }
 
+// Validate the value is a project tag. Tags are IDs that may also contain colons,
+// so that they can be namespaced, like "scope:billing".
+pub fn validate_tag(key: &str, tag: &str) -> Result<(), ValidationError> {
+    if !matches_tag(tag) {
+        return Err(create_validation_error(
+            "invalid_tag",
+            key,
+            String::from("Must be a valid tag (accepts A-Z, a-z, 0-9, - (dashes), _ (underscores), : (colons), and must start with a letter)"),
+        ));
+    }
+
+    Ok(())
+}
+
 // Validate the value is a target in the format of "project_id:task_id".
 pub fn validate_target(key: &str, target_id: &str) -> Result<(), ValidationError> {
     if !matches_target(target_id) {
@@ -255,6 +269,29 @@ mod tests {
         }
     }
 
+    mod validate_tag {
+        use super::*;
+
+        #[test]
+        fn supports_cases() {
+            assert!(validate_tag("key", "frontend").is_ok());
+            assert!(validate_tag("key", "foo-bar_baz1").is_ok());
+        }
+
+        #[test]
+        fn supports_colons() {
+            assert!(validate_tag("key", "scope:billing").is_ok());
+            assert!(validate_tag("key", "type:ui:react").is_ok());
+        }
+
+        #[test]
+        fn fails_if_starts_with_nonchar() {
+            assert!(validate_tag("key", "1foo").is_err());
+            assert!(validate_tag("key", ":foo").is_err());
+            assert!(validate_tag("key", "#foo").is_err());
+        }
+    }
+
     mod validate_target {
         use super::*;
 
@@ -277,6 +314,8 @@ mod tests {
             assert!(validate_target("key", "^:a").is_ok());
             assert!(validate_target("key", "~:b").is_ok());
             assert!(validate_target("key", ":c").is_ok());
+            assert!(validate_target("key", "#tag:d").is_ok());
+            assert!(validate_target("key", "#scope:billing:e").is_ok());
         }
 
         #[test]
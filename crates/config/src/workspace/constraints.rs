@@ -0,0 +1,32 @@
+use crate::validators::validate_tag;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use validator::{Validate, ValidationError};
+
+fn validate_tag_relationships(map: &HashMap<String, Vec<String>>) -> Result<(), ValidationError> {
+    for (tag, allowed_tags) in map {
+        let key = format!("constraints.tagRelationships.{}", tag);
+
+        validate_tag(&key, tag)?;
+
+        for (index, allowed_tag) in allowed_tags.iter().enumerate() {
+            validate_tag(&format!("{}[{}]", key, index), allowed_tag)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[schemars(default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintsConfig {
+    /// Applications and libraries cannot depend on applications.
+    pub enforce_project_type_relationships: bool,
+
+    /// Projects with a tag (the key) can only depend on projects
+    /// that have the same tag, or one of the listed tags.
+    #[validate(custom = "validate_tag_relationships")]
+    pub tag_relationships: HashMap<String, Vec<String>>,
+}
@@ -1,20 +1,24 @@
 // .moon/workspace.yml
 
+mod constraints;
 pub mod node;
+mod remote_cache;
 mod typescript;
 mod vcs;
 
 use crate::constants;
-use crate::errors::map_validation_errors_to_figment_errors;
+use crate::errors::{create_validation_error, map_validation_errors_to_figment_errors};
 use crate::providers::url::Url;
 use crate::types::{FileGlob, FilePath};
 use crate::validators::{validate_child_relative_path, validate_extends, validate_id};
+pub use constraints::ConstraintsConfig;
 use figment::{
     providers::{Format, Serialized, Yaml},
     Error as FigmentError, Figment,
 };
 use moon_utils::string_vec;
 pub use node::{NodeConfig, NpmConfig, PackageManager, PnpmConfig, YarnConfig};
+pub use remote_cache::{RemoteCacheConfig, RemoteCacheProvider};
 use schemars::gen::SchemaGenerator;
 use schemars::schema::Schema;
 use schemars::{schema_for, JsonSchema};
@@ -50,10 +54,27 @@ fn validate_projects(projects: &ProjectsMap) -> Result<(), ValidationError> {
     Ok(())
 }
 
+fn validate_concurrency(value: usize) -> Result<(), ValidationError> {
+    if value == 0 {
+        return Err(create_validation_error(
+            "invalid_concurrency",
+            "actionRunner.concurrency",
+            String::from("Must be greater than 0"),
+        ));
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Debug, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
 #[schemars(default)]
 #[serde(rename_all = "camelCase")]
 pub struct ActionRunnerConfig {
+    /// Maximum amount of actions to run in parallel.
+    /// Defaults to the amount of available CPUs.
+    #[validate(custom = "validate_concurrency")]
+    pub concurrency: Option<usize>,
+
     pub implicit_inputs: Vec<String>,
 
     pub inherit_colors_for_piped_tasks: bool,
@@ -64,6 +85,7 @@ pub struct ActionRunnerConfig {
 impl Default for ActionRunnerConfig {
     fn default() -> Self {
         ActionRunnerConfig {
+            concurrency: None,
             implicit_inputs: string_vec![
                 // When a project changes
                 "package.json",
@@ -85,6 +107,9 @@ pub struct WorkspaceConfig {
     #[validate]
     pub action_runner: ActionRunnerConfig,
 
+    #[validate]
+    pub constraints: ConstraintsConfig,
+
     #[validate(custom = "validate_extends")]
     pub extends: Option<String>,
 
@@ -96,6 +121,9 @@ pub struct WorkspaceConfig {
     #[validate(custom = "validate_projects")]
     pub projects: ProjectsMap,
 
+    #[validate]
+    pub remote_cache: Option<RemoteCacheConfig>,
+
     #[validate]
     pub typescript: TypeScriptConfig,
 
@@ -268,9 +296,11 @@ mod tests {
                 config,
                 WorkspaceConfig {
                     action_runner: ActionRunnerConfig::default(),
+                    constraints: ConstraintsConfig::default(),
                     extends: None,
                     node: NodeConfig::default(),
                     projects: HashMap::new(),
+                    remote_cache: None,
                     typescript: TypeScriptConfig::default(),
                     vcs: VcsConfig::default(),
                     schema: String::new(),
@@ -475,12 +505,14 @@ node:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        constraints: ConstraintsConfig::default(),
                         extends: None,
                         node: NodeConfig {
                             package_manager: PackageManager::Yarn,
                             ..NodeConfig::default()
                         },
                         projects: HashMap::new(),
+                        remote_cache: None,
                         typescript: TypeScriptConfig::default(),
                         vcs: VcsConfig::default(),
                         schema: String::new(),
@@ -980,9 +1012,11 @@ vcs:
                     config,
                     WorkspaceConfig {
                         action_runner: ActionRunnerConfig::default(),
+                        constraints: ConstraintsConfig::default(),
                         extends: None,
                         node: NodeConfig::default(),
                         projects: HashMap::new(),
+                        remote_cache: None,
                         typescript: TypeScriptConfig::default(),
                         vcs: VcsConfig {
                             manager: VcsManager::Svn,
@@ -1055,4 +1089,189 @@ vcs:
             });
         }
     }
+
+    mod action_runner {
+        use super::*;
+
+        #[test]
+        fn loads_concurrency() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+actionRunner:
+    concurrency: 4"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(config.action_runner.concurrency, Some(4));
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Must be greater than 0")]
+        fn invalid_concurrency() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+actionRunner:
+    concurrency: 0"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
+    mod constraints {
+        use super::*;
+
+        #[test]
+        fn loads_tag_relationships() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    enforceProjectTypeRelationships: true
+    tagRelationships:
+        scope:billing: ['scope:shared']"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.constraints,
+                    ConstraintsConfig {
+                        enforce_project_type_relationships: true,
+                        tag_relationships: HashMap::from([(
+                            "scope:billing".to_owned(),
+                            string_vec!["scope:shared"]
+                        )]),
+                    }
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "Must be a valid tag")]
+        fn invalid_tag() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+constraints:
+    tagRelationships:
+        scope:billing: ['#shared']"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
+
+    mod remote_cache {
+        use super::*;
+
+        #[test]
+        fn loads_http_provider() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+remoteCache:
+    provider: http
+    location: https://cache.example.com/moon"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.remote_cache,
+                    Some(RemoteCacheConfig {
+                        location: String::from("https://cache.example.com/moon"),
+                        provider: RemoteCacheProvider::Http,
+                    })
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        fn defaults_to_filesystem_provider() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+remoteCache:
+    location: ../shared-cache"#,
+                )?;
+
+                let config = super::load_jailed_config(jail.directory())?;
+
+                assert_eq!(
+                    config.remote_cache.unwrap().provider,
+                    RemoteCacheProvider::Filesystem
+                );
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(
+            expected = "unknown variant: found `s3`, expected ``filesystem` or `http`` for key \"workspace.remoteCache.provider\""
+        )]
+        fn invalid_provider_option() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+remoteCache:
+    provider: s3
+    location: bucket"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+
+        #[test]
+        #[should_panic(expected = "A directory path or URL is required")]
+        fn empty_location() {
+            figment::Jail::expect_with(|jail| {
+                jail.create_file(
+                    super::constants::CONFIG_WORKSPACE_FILENAME,
+                    r#"
+projects: {}
+remoteCache:
+    location: ''"#,
+                )?;
+
+                super::load_jailed_config(jail.directory())?;
+
+                Ok(())
+            });
+        }
+    }
 }
@@ -0,0 +1,36 @@
+use crate::errors::create_validation_error;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+fn validate_location(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() {
+        return Err(create_validation_error(
+            "required_location",
+            "remoteCache.location",
+            String::from("A directory path or URL is required"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteCacheProvider {
+    #[default]
+    Filesystem,
+    Http,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema, PartialEq, Serialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteCacheConfig {
+    /// A directory path (relative from the workspace root) when using the
+    /// filesystem provider, or a base URL when using the HTTP provider.
+    #[validate(custom = "validate_location")]
+    pub location: String,
+
+    #[serde(default)]
+    pub provider: RemoteCacheProvider,
+}
@@ -10,11 +10,14 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum MoonError {
+    #[error("Archived file <path>{0}</path> is not a relative path, refusing to write it outside of its destination.")]
+    ArchiveUnsafePath(String),
+
     #[error("File system failure for <path>{0}</path>: {1}")]
     FileSystem(PathBuf, #[source] IoError),
 
-    #[error("Failed to create a hard link from <path>{0}</path> to <path>{1}</path>.")]
-    HardLink(PathBuf, PathBuf),
+    #[error("HTTP request to <url>{0}</url> failed: {1}")]
+    Http(String, String),
 
     #[error("Failed to parse <path>{0}</path>: {1}")]
     Json(PathBuf, #[source] JsonError),
@@ -37,6 +40,9 @@ pub enum MoonError {
     #[error("Process <shell>{0}</shell> failed with a <symbol>{1}</symbol> exit code.\n<muted>{2}</muted>")]
     ProcessNonZeroWithOutput(String, i32, String),
 
+    #[error("Process <shell>{0}</shell> timed out after <symbol>{1}</symbol> seconds.")]
+    ProcessTimeout(String, u64),
+
     #[error(transparent)]
     Regex(#[from] RegexError),
 
@@ -4,9 +4,10 @@ use moon_utils::path;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::BTreeMap;
+use std::env;
 
 #[derive(Default, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct TargetHasher {
     // Task `command`
     command: String,
@@ -49,6 +50,34 @@ pub struct TargetHasher {
     version: String,
 }
 
+fn get_value_digest(value: &str) -> String {
+    let mut sha = Sha256::new();
+    sha.update(value.as_bytes());
+
+    format!("{:x}", sha.finalize())
+}
+
+fn diff_btree(
+    label: &str,
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+    reasons: &mut Vec<String>,
+) {
+    for (key, value) in current {
+        match previous.get(key) {
+            Some(previous_value) if previous_value == value => {}
+            Some(_) => reasons.push(format!("{} `{}` changed", label, key)),
+            None => reasons.push(format!("{} `{}` added", label, key)),
+        };
+    }
+
+    for key in previous.keys() {
+        if !current.contains_key(key) {
+            reasons.push(format!("{} `{}` removed", label, key));
+        }
+    }
+}
+
 impl TargetHasher {
     pub fn new(node_version: String) -> Self {
         TargetHasher {
@@ -69,6 +98,76 @@ impl TargetHasher {
         }
     }
 
+    /// Compare against the hasher of a previous run, and return a list of
+    /// reasons for why the hashes differ. Values are never included in the
+    /// reasons, as environment variables may contain secrets.
+    pub fn diff(&self, previous: &TargetHasher) -> Vec<String> {
+        let mut reasons = vec![];
+
+        if self.version != previous.version {
+            reasons.push(String::from("hasher version changed"));
+        }
+
+        if self.node_version != previous.node_version {
+            reasons.push(String::from("node version changed"));
+        }
+
+        if self.command != previous.command {
+            reasons.push(String::from("command changed"));
+        }
+
+        if self.args != previous.args {
+            reasons.push(String::from("args changed"));
+        }
+
+        if self.deps != previous.deps {
+            reasons.push(String::from("deps changed"));
+        }
+
+        diff_btree("env var", &previous.env_vars, &self.env_vars, &mut reasons);
+        diff_btree(
+            "input",
+            &previous.input_hashes,
+            &self.input_hashes,
+            &mut reasons,
+        );
+
+        if self.project_deps != previous.project_deps {
+            reasons.push(String::from("project dependencies changed"));
+        }
+
+        diff_btree(
+            "dependency",
+            &previous.package_dependencies,
+            &self.package_dependencies,
+            &mut reasons,
+        );
+        diff_btree(
+            "dev dependency",
+            &previous.package_dev_dependencies,
+            &self.package_dev_dependencies,
+            &mut reasons,
+        );
+        diff_btree(
+            "peer dependency",
+            &previous.package_peer_dependencies,
+            &self.package_peer_dependencies,
+            &mut reasons,
+        );
+        diff_btree(
+            "tsconfig compiler option",
+            &previous.tsconfig_compiler_options,
+            &self.tsconfig_compiler_options,
+            &mut reasons,
+        );
+
+        if self.target != previous.target {
+            reasons.push(String::from("target changed"));
+        }
+
+        reasons
+    }
+
     /// Hash a mapping of input file paths to unique file hashes.
     /// File paths *must* be relative from the workspace root.
     pub fn hash_inputs(&mut self, inputs: BTreeMap<String, String>) {
@@ -101,12 +200,24 @@ impl TargetHasher {
     }
 
     /// Hash `args`, `inputs`, `deps`, and `env` vars from a task.
+    /// Environment variables declared as inputs (`$NAME`) are hashed with a digest
+    /// of their current value, as the manifest is persisted and shared through the
+    /// remote cache, and the value may be a secret. They are overridden by the task's `env`.
     pub fn hash_task(&mut self, task: &Task) {
         self.command = task.command.clone();
         self.args = task.args.clone();
         self.deps = task.deps.clone();
         self.target = task.target.clone();
 
+        for var in &task.input_vars {
+            self.env_vars.insert(
+                var.clone(),
+                get_value_digest(&env::var(var).unwrap_or_default()),
+            );
+        }
+
+        self.env_vars.extend(task.env.clone());
+
         // Sort vectors to be deterministic
         self.args.sort();
         self.deps.sort();
@@ -180,6 +291,7 @@ impl TargetHasher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     #[test]
     fn returns_default_hash() {
@@ -264,6 +376,173 @@ mod tests {
         }
     }
 
+    mod diff {
+        use super::*;
+        use moon_config::TaskConfig;
+        use moon_utils::string_vec;
+
+        #[test]
+        fn returns_nothing_for_same_contents() {
+            let hasher1 = TargetHasher::new(String::from("0.0.0"));
+            let hasher2 = TargetHasher::new(String::from("0.0.0"));
+
+            assert!(hasher2.diff(&hasher1).is_empty());
+        }
+
+        #[test]
+        fn returns_scalar_changes() {
+            let hasher1 = TargetHasher::new(String::from("16.0.0"));
+            let mut hasher2 = TargetHasher::new(String::from("18.0.0"));
+
+            hasher2.hash_args(&string_vec!["--force"]);
+
+            assert_eq!(
+                hasher2.diff(&hasher1),
+                string_vec!["node version changed", "args changed"]
+            );
+        }
+
+        #[test]
+        fn returns_changes_per_key() {
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_inputs(BTreeMap::from([
+                ("src/a.ts".to_owned(), "1".to_owned()),
+                ("src/b.ts".to_owned(), "2".to_owned()),
+                ("src/c.ts".to_owned(), "3".to_owned()),
+            ]));
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_inputs(BTreeMap::from([
+                ("src/a.ts".to_owned(), "1".to_owned()),
+                ("src/b.ts".to_owned(), "4".to_owned()),
+                ("src/d.ts".to_owned(), "5".to_owned()),
+            ]));
+
+            assert_eq!(
+                hasher2.diff(&hasher1),
+                string_vec![
+                    "input `src/b.ts` changed",
+                    "input `src/d.ts` added",
+                    "input `src/c.ts` removed"
+                ]
+            );
+        }
+
+        #[test]
+        fn doesnt_include_env_values() {
+            let mut hasher1 = TargetHasher::new(String::from("0.0.0"));
+            hasher1.hash_task(&Task::from_config(
+                String::from("project:task"),
+                &TaskConfig {
+                    env: Some(HashMap::from([("SECRET".to_owned(), "abc".to_owned())])),
+                    ..TaskConfig::default()
+                },
+            ));
+
+            let mut hasher2 = TargetHasher::new(String::from("0.0.0"));
+            hasher2.hash_task(&Task::from_config(
+                String::from("project:task"),
+                &TaskConfig {
+                    env: Some(HashMap::from([("SECRET".to_owned(), "xyz".to_owned())])),
+                    ..TaskConfig::default()
+                },
+            ));
+
+            assert_eq!(
+                hasher2.diff(&hasher1),
+                string_vec!["env var `SECRET` changed"]
+            );
+        }
+    }
+
+    mod env_vars {
+        use super::*;
+        use moon_config::TaskConfig;
+
+        fn create_task(env: &[(&str, &str)], inputs: &[&str]) -> Task {
+            let mut task = Task::from_config(
+                String::from("project:task"),
+                &TaskConfig {
+                    env: Some(HashMap::from_iter(
+                        env.iter().map(|(k, v)| (k.to_string(), v.to_string())),
+                    )),
+                    ..TaskConfig::default()
+                },
+            );
+
+            task.input_vars
+                .extend(inputs.iter().map(|var| var.to_string()));
+            task
+        }
+
+        fn hash_task(task: &Task) -> String {
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_task(task);
+            hasher.to_hash()
+        }
+
+        #[test]
+        fn returns_diff_hash_for_task_env() {
+            let hash1 = hash_task(&create_task(&[("KEY", "a")], &[]));
+            let hash2 = hash_task(&create_task(&[("KEY", "b")], &[]));
+            let hash3 = hash_task(&create_task(&[], &[]));
+
+            assert_ne!(hash1, hash2);
+            assert_ne!(hash1, hash3);
+            assert_ne!(hash2, hash3);
+        }
+
+        #[test]
+        fn returns_diff_hash_for_input_env_value() {
+            let task = create_task(&[], &["MOON_HASHER_TEST_INPUT"]);
+
+            env::remove_var("MOON_HASHER_TEST_INPUT");
+            let hash1 = hash_task(&task);
+
+            env::set_var("MOON_HASHER_TEST_INPUT", "a");
+            let hash2 = hash_task(&task);
+
+            env::set_var("MOON_HASHER_TEST_INPUT", "b");
+            let hash3 = hash_task(&task);
+
+            env::remove_var("MOON_HASHER_TEST_INPUT");
+
+            assert_ne!(hash1, hash2);
+            assert_ne!(hash1, hash3);
+            assert_ne!(hash2, hash3);
+        }
+
+        #[test]
+        fn doesnt_include_input_env_value_in_manifest() {
+            env::set_var("MOON_HASHER_TEST_SECRET", "super-secret-value");
+
+            let mut hasher = TargetHasher::new(String::from("0.0.0"));
+            hasher.hash_task(&create_task(&[], &["MOON_HASHER_TEST_SECRET"]));
+
+            env::remove_var("MOON_HASHER_TEST_SECRET");
+
+            let manifest = serde_json::to_string(&hasher).unwrap();
+
+            assert!(manifest.contains("MOON_HASHER_TEST_SECRET"));
+            assert!(!manifest.contains("super-secret-value"));
+        }
+
+        #[test]
+        fn task_env_overrides_input_env() {
+            env::set_var("MOON_HASHER_TEST_OVERRIDE", "outer");
+
+            let hash1 = hash_task(&create_task(
+                &[("MOON_HASHER_TEST_OVERRIDE", "inner")],
+                &["MOON_HASHER_TEST_OVERRIDE"],
+            ));
+            let hash2 = hash_task(&create_task(&[("MOON_HASHER_TEST_OVERRIDE", "inner")], &[]));
+
+            env::remove_var("MOON_HASHER_TEST_OVERRIDE");
+
+            assert_eq!(hash1, hash2);
+        }
+    }
+
     mod package_json {
         use super::*;
 
@@ -0,0 +1,70 @@
+use crate::errors::ProjectError;
+use crate::project::Project;
+use moon_config::ProjectType;
+use std::collections::HashMap;
+
+/// Applications and libraries cannot depend on applications.
+/// All other relationships, including projects with an unknown type, are not constrained.
+pub fn enforce_project_type_relationships(
+    project: &Project,
+    dependency: &Project,
+) -> Result<(), ProjectError> {
+    let valid = !matches!(
+        (&project.config.type_of, &dependency.config.type_of),
+        (
+            ProjectType::Application | ProjectType::Library,
+            ProjectType::Application
+        )
+    );
+
+    if !valid {
+        return Err(ProjectError::InvalidTypeRelationship(
+            project.id.clone(),
+            dependency.id.clone(),
+            project.config.type_of.to_string(),
+            dependency.config.type_of.to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// For each tag of the project that has been constrained, the dependency must have
+/// the same tag, or one of the allowed tags.
+pub fn enforce_tag_relationships(
+    project: &Project,
+    dependency: &Project,
+    relationships: &HashMap<String, Vec<String>>,
+) -> Result<(), ProjectError> {
+    for tag in &project.config.tags {
+        let allowed_tags = match relationships.get(tag) {
+            Some(tags) => tags,
+            None => continue,
+        };
+
+        let valid = dependency
+            .config
+            .tags
+            .iter()
+            .any(|dep_tag| dep_tag == tag || allowed_tags.contains(dep_tag));
+
+        if !valid {
+            let mut expected_tags = vec![tag.clone()];
+
+            for allowed_tag in allowed_tags {
+                if !expected_tags.contains(allowed_tag) {
+                    expected_tags.push(allowed_tag.clone());
+                }
+            }
+
+            return Err(ProjectError::InvalidTagRelationship(
+                project.id.clone(),
+                dependency.id.clone(),
+                tag.clone(),
+                expected_tags.join(", "),
+            ));
+        }
+    }
+
+    Ok(())
+}
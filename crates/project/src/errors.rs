@@ -15,6 +15,16 @@ pub enum ProjectError {
     )]
     InvalidConfigFile(String, String),
 
+    #[error(
+        "Invalid dependency from <id>{0}</id> to <id>{1}</id>. Projects tagged <symbol>{2}</symbol> can only depend on projects tagged <symbol>{3}</symbol>."
+    )]
+    InvalidTagRelationship(String, String, String, String),
+
+    #[error(
+        "Invalid dependency from <id>{0}</id> to <id>{1}</id>. A project of type <symbol>{2}</symbol> cannot depend on a project of type <symbol>{3}</symbol>."
+    )]
+    InvalidTypeRelationship(String, String, String, String),
+
     #[error("No project exists at path <file>{0}</file>.")]
     MissingProject(String),
 
@@ -62,10 +72,17 @@ pub enum TargetError {
     )]
     NoProjectAllInTaskDeps(String),
 
-    #[error("Project dependencies scope (^:) is not supported in run contexts.")]
+    #[error(
+        "Project tag scope (#tag:) is not supported in task deps, for target <target>{0}</target>."
+    )]
+    NoProjectTagInTaskDeps(String),
+
+    #[error(
+        "Project dependencies scope (^:) in run contexts requires a project working directory."
+    )]
     NoProjectDepsInRunContext,
 
-    #[error("Project self scope (~:) is not supported in run contexts.")]
+    #[error("Project self scope (~:) in run contexts requires a project working directory.")]
     NoProjectSelfInRunContext,
 }
 
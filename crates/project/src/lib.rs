@@ -1,9 +1,11 @@
 mod constants;
+mod constraints;
 mod errors;
 mod file_group;
 mod helpers;
 mod project;
 mod project_graph;
+mod scripts;
 mod target;
 mod task;
 pub mod test;
@@ -18,7 +20,7 @@ pub use types::*;
 // Projects
 pub use moon_config::{ProjectID, ProjectType};
 pub use project::{Project, ProjectsMap};
-pub use project_graph::ProjectGraph;
+pub use project_graph::{ProjectGraph, ProjectGraphEdge, ProjectGraphEdgeType, ProjectGraphJson};
 
 // Tasks & targets
 pub use moon_config::{TargetID, TaskID, TaskType};
@@ -1,12 +1,15 @@
 use crate::errors::ProjectError;
 use crate::file_group::FileGroup;
+use crate::scripts::create_tasks_from_scripts;
 use crate::target::Target;
 use crate::task::Task;
 use crate::token::{TokenResolver, TokenSharedData};
 use moon_config::constants::CONFIG_PROJECT_FILENAME;
 use moon_config::{
-    format_figment_errors, FilePath, GlobalProjectConfig, ProjectConfig, ProjectID, TaskID,
+    format_figment_errors, FilePath, GlobalProjectConfig, ProjectConfig, ProjectID, TaskConfig,
+    TaskID,
 };
+use moon_lang_node::package::ScriptsSet;
 use moon_logger::{color, debug, trace, Logable};
 use moon_utils::path;
 use serde::{Deserialize, Serialize};
@@ -88,6 +91,7 @@ fn create_tasks_from_config(
     project_id: &str,
     project_config: &ProjectConfig,
     global_config: &GlobalProjectConfig,
+    inferred_tasks: &HashMap<TaskID, TaskConfig>,
     token_data: &TokenSharedData,
     implicit_inputs: &[String],
 ) -> Result<TasksMap, ProjectError> {
@@ -174,7 +178,25 @@ fn create_tasks_from_config(
         );
     }
 
-    // Add local tasks second
+    // Add tasks inferred from `package.json` scripts second
+    for (task_id, task_config) in inferred_tasks {
+        if tasks.contains_key(task_id) {
+            debug!(
+                target: log_target,
+                "Merging inferred task {} with global config",
+                color::id(task_id)
+            );
+
+            tasks.get_mut(task_id).unwrap().merge(task_config);
+        } else {
+            tasks.insert(
+                task_id.clone(),
+                Task::from_config(Target::format(project_id, task_id)?, task_config),
+            );
+        }
+    }
+
+    // Add local tasks last
     for (task_id, task_config) in &project_config.tasks {
         if tasks.contains_key(task_id) {
             debug!(
@@ -261,6 +283,8 @@ impl Project {
         workspace_root: &Path,
         global_config: &GlobalProjectConfig,
         implicit_inputs: &[String],
+        implicit_depends_on: &[ProjectID],
+        package_scripts: Option<&ScriptsSet>,
     ) -> Result<Project, ProjectError> {
         let root = workspace_root.join(path::normalize_separators(source));
         let log_target = format!("moon:project:{}", id);
@@ -277,7 +301,30 @@ impl Project {
             return Err(ProjectError::MissingProject(String::from(source)));
         }
 
-        let config = load_project_config(&log_target, &root, source)?;
+        let mut config = load_project_config(&log_target, &root, source)?;
+
+        // Merge implicit dependencies (for example, inferred from `package.json`)
+        // before creating tasks, so that they're available to the `^:` scope
+        for dep_id in implicit_depends_on {
+            if dep_id != id && !config.depends_on.contains(dep_id) {
+                trace!(
+                    target: &log_target,
+                    "Adding implicit dependency {}",
+                    color::id(dep_id),
+                );
+
+                config.depends_on.push(dep_id.to_owned());
+            }
+        }
+
+        // Convert `package.json` scripts to tasks when inferring is enabled
+        let inferred_tasks = match package_scripts {
+            Some(scripts) => {
+                create_tasks_from_scripts(&log_target, scripts, &config.workspace.inferred_tasks)
+            }
+            None => HashMap::new(),
+        };
+
         let file_groups = create_file_groups_from_config(&log_target, &config, global_config);
         let token_data = TokenSharedData::new(&file_groups, workspace_root, &root, &config);
         let tasks = create_tasks_from_config(
@@ -285,6 +332,7 @@ impl Project {
             id,
             &config,
             global_config,
+            &inferred_tasks,
             &token_data,
             implicit_inputs,
         )?;
@@ -1,16 +1,21 @@
 use crate::constants::ROOT_NODE_ID;
+use crate::constraints::{enforce_project_type_relationships, enforce_tag_relationships};
 use crate::errors::ProjectError;
 use crate::helpers::detect_projects_with_globs;
 use crate::project::Project;
 use crate::types::ProjectsSourceMap;
 use moon_cache::CacheEngine;
 use moon_config::constants::FLAG_PROJECTS_USING_GLOB;
-use moon_config::{GlobalProjectConfig, ProjectID, WorkspaceConfig};
-use moon_logger::{color, debug, map_list, trace};
+use moon_config::{ConstraintsConfig, GlobalProjectConfig, ProjectID, WorkspaceConfig};
+use moon_lang_node::package::{PackageJson, ScriptsSet};
+use moon_logger::{color, debug, map_list, trace, warn};
+use moon_utils::graph::{self, GraphEdges, GraphNodes};
+use moon_utils::path;
 use petgraph::dot::{Config, Dot};
 use petgraph::graph::{DiGraph, NodeIndex};
 use petgraph::visit::EdgeRef;
 use petgraph::Direction;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock, RwLockWriteGuard};
@@ -19,9 +24,39 @@ type GraphType = DiGraph<Project, ()>;
 type IndicesType = HashMap<ProjectID, NodeIndex>;
 
 const LOG_TARGET: &str = "moon:project-graph";
+const JSON_VERSION: u8 = 1;
 const READ_ERROR: &str = "Failed to acquire a read lock";
 const WRITE_ERROR: &str = "Failed to acquire a write lock";
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProjectGraphEdgeType {
+    DependsOn,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGraphEdge {
+    pub source: ProjectID,
+
+    pub target: ProjectID,
+
+    #[serde(rename = "type")]
+    pub type_of: ProjectGraphEdgeType,
+}
+
+/// A stable representation of the project graph, that can be consumed by other tools.
+/// Nodes and edges are sorted by project ID, and the version is bumped on breaking changes.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGraphJson {
+    pub edges: Vec<ProjectGraphEdge>,
+
+    pub nodes: Vec<Project>,
+
+    pub version: u8,
+}
+
 async fn load_projects_from_cache(
     workspace_root: &Path,
     projects: &ProjectsSourceMap,
@@ -72,7 +107,105 @@ async fn load_projects_from_cache(
     Ok(map)
 }
 
+/// Load the `package.json` of each project, if one exists.
+async fn load_package_jsons(
+    workspace_root: &Path,
+    projects: &ProjectsSourceMap,
+) -> Result<HashMap<ProjectID, PackageJson>, ProjectError> {
+    let mut package_jsons = HashMap::new();
+
+    for (project_id, source) in projects {
+        let package_path = workspace_root
+            .join(path::normalize_separators(source))
+            .join("package.json");
+
+        if let Some(package_json) = PackageJson::read(package_path).await? {
+            package_jsons.insert(project_id.clone(), package_json);
+        }
+    }
+
+    Ok(package_jsons)
+}
+
+/// Infer dependencies between projects by matching the names of packages in each project's
+/// `package.json` `dependencies` and `devDependencies` against the names of other projects.
+/// Package names that are used by multiple projects are ambiguous, and are not inferred.
+fn infer_depends_on_from_package_json(
+    package_jsons: &HashMap<ProjectID, PackageJson>,
+) -> HashMap<ProjectID, Vec<ProjectID>> {
+    let mut package_names = HashMap::new();
+
+    debug!(
+        target: LOG_TARGET,
+        "Inferring project dependencies from {}",
+        color::file("package.json")
+    );
+
+    let mut projects_by_name: HashMap<&String, Vec<&ProjectID>> = HashMap::new();
+
+    for (project_id, package_json) in package_jsons {
+        if let Some(name) = &package_json.name {
+            projects_by_name.entry(name).or_default().push(project_id);
+        }
+    }
+
+    // Which project a duplicate package name refers to can not be determined,
+    // so avoid inferring a dependency on any of them
+    for (name, mut project_ids) in projects_by_name {
+        if project_ids.len() > 1 {
+            project_ids.sort();
+
+            warn!(
+                target: LOG_TARGET,
+                "Package {} is used by multiple projects ({}), unable to infer dependencies on it",
+                color::symbol(name),
+                map_list(&project_ids, |id| color::id(id)),
+            );
+
+            continue;
+        }
+
+        package_names.insert(name, project_ids[0]);
+    }
+
+    let mut depends_on = HashMap::new();
+
+    for (project_id, package_json) in package_jsons {
+        let mut dep_ids = vec![];
+
+        for deps in [&package_json.dependencies, &package_json.dev_dependencies]
+            .into_iter()
+            .flatten()
+        {
+            for name in deps.keys() {
+                if let Some(dep_id) = package_names.get(name) {
+                    if *dep_id != project_id && !dep_ids.contains(*dep_id) {
+                        dep_ids.push(dep_id.to_string());
+                    }
+                }
+            }
+        }
+
+        if !dep_ids.is_empty() {
+            trace!(
+                target: LOG_TARGET,
+                "Inferred dependencies {} for project {}",
+                map_list(&dep_ids, |d| color::symbol(d)),
+                color::id(project_id),
+            );
+
+            depends_on.insert(project_id.clone(), dep_ids);
+        }
+    }
+
+    depends_on
+}
+
 pub struct ProjectGraph {
+    /// Constraints that dependencies between projects must satisfy.
+    /// Is the `constraints` setting in `.moon/workspace.yml`.
+    constraints: ConstraintsConfig,
+
     /// The global project configuration that all projects inherit from.
     /// Is loaded from `.moon/project.yml`.
     global_config: GlobalProjectConfig,
@@ -80,6 +213,10 @@ pub struct ProjectGraph {
     /// Projects that have been loaded into scope represented as a DAG.
     graph: Arc<RwLock<GraphType>>,
 
+    /// Dependencies of each project that were not explicitly configured,
+    /// but inferred from `package.json`. Is merged with `dependsOn`.
+    implicit_depends_on: HashMap<ProjectID, Vec<ProjectID>>,
+
     /// Inputs to be inherited by all tasks.
     implicit_inputs: Vec<String>,
 
@@ -87,6 +224,10 @@ pub struct ProjectGraph {
     /// to query the graph by ID as it only supports it by index.
     indices: Arc<RwLock<IndicesType>>,
 
+    /// Scripts from each project's `package.json`, to infer tasks from.
+    /// Is only populated when inferring tasks is enabled.
+    package_scripts: HashMap<ProjectID, ScriptsSet>,
+
     /// The mapping of projects by ID to a relative file system location.
     /// Is the `projects` setting in `.moon/workspace.yml`.
     projects_map: HashMap<ProjectID, String>,
@@ -118,17 +259,42 @@ impl ProjectGraph {
             ..Project::default()
         });
 
+        let projects_map =
+            load_projects_from_cache(workspace_root, &workspace_config.projects, cache).await?;
+
+        let node_config = &workspace_config.node;
+
+        let package_jsons =
+            if node_config.infer_project_dependencies || node_config.infer_tasks_from_scripts {
+                load_package_jsons(workspace_root, &projects_map).await?
+            } else {
+                HashMap::new()
+            };
+
+        let implicit_depends_on = if node_config.infer_project_dependencies {
+            infer_depends_on_from_package_json(&package_jsons)
+        } else {
+            HashMap::new()
+        };
+
+        let package_scripts = if node_config.infer_tasks_from_scripts {
+            package_jsons
+                .into_iter()
+                .map(|(id, package_json)| (id, package_json.scripts.unwrap_or_default()))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
         Ok(ProjectGraph {
+            constraints: workspace_config.constraints.clone(),
             global_config,
             graph: Arc::new(RwLock::new(graph)),
+            implicit_depends_on,
             implicit_inputs: workspace_config.action_runner.implicit_inputs.clone(),
             indices: Arc::new(RwLock::new(HashMap::new())),
-            projects_map: load_projects_from_cache(
-                workspace_root,
-                &workspace_config.projects,
-                cache,
-            )
-            .await?,
+            package_scripts,
+            projects_map,
             workspace_root: workspace_root.to_path_buf(),
         })
     }
@@ -181,6 +347,26 @@ impl ProjectGraph {
         Ok(())
     }
 
+    /// Return the ID of the project that contains the provided absolute path.
+    /// When projects are nested, the deepest project is returned.
+    pub fn find_project_id_from_path(&self, path: &Path) -> Option<ProjectID> {
+        let rel_path = path.strip_prefix(&self.workspace_root).ok()?;
+
+        self.projects_map
+            .iter()
+            // A project at the workspace root (source of ".") contains every path,
+            // but should only match when a more specific project does not
+            .filter(|(_, source)| source.as_str() == "." || rel_path.starts_with(source))
+            .max_by_key(|(_, source)| {
+                if source.as_str() == "." {
+                    0
+                } else {
+                    source.len()
+                }
+            })
+            .map(|(id, _)| id.to_owned())
+    }
+
     /// Return a list of direct project IDs that the defined project depends on.
     #[track_caller]
     pub fn get_dependencies_of(&self, project: &Project) -> Result<Vec<ProjectID>, ProjectError> {
@@ -248,6 +434,96 @@ impl ProjectGraph {
         format!("{:?}", dot)
     }
 
+    /// Format as a GraphML document.
+    pub fn to_graphml(&self) -> String {
+        let (nodes, edges) = self.get_labeled_nodes_and_edges();
+
+        graph::to_graphml(&nodes, &edges)
+    }
+
+    /// Format as a JSON serializable structure, with all project metadata.
+    pub fn to_json(&self) -> ProjectGraphJson {
+        let graph = self.graph.read().expect(READ_ERROR);
+
+        let mut nodes = graph
+            .node_indices()
+            .filter(|index| index.index() != 0)
+            .map(|index| graph[index].clone())
+            .collect::<Vec<_>>();
+
+        nodes.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut edges = graph
+            .raw_edges()
+            .iter()
+            .filter(|edge| edge.source().index() != 0)
+            .map(|edge| ProjectGraphEdge {
+                source: graph[edge.source()].id.clone(),
+                target: graph[edge.target()].id.clone(),
+                type_of: ProjectGraphEdgeType::DependsOn,
+            })
+            .collect::<Vec<_>>();
+
+        edges.sort_by(|a, b| a.source.cmp(&b.source).then(a.target.cmp(&b.target)));
+
+        ProjectGraphJson {
+            edges,
+            nodes,
+            version: JSON_VERSION,
+        }
+    }
+
+    /// Format as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let (nodes, edges) = self.get_labeled_nodes_and_edges();
+
+        graph::to_mermaid(&nodes, &edges)
+    }
+
+    /// The root node only exists to connect all projects, so is excluded.
+    fn get_labeled_nodes_and_edges(&self) -> (GraphNodes, GraphEdges) {
+        let graph = self.graph.read().expect(READ_ERROR);
+
+        let nodes = graph
+            .node_indices()
+            .filter(|index| index.index() != 0)
+            .map(|index| (format!("n{}", index.index()), graph[index].id.clone()))
+            .collect();
+
+        let edges = graph
+            .raw_edges()
+            .iter()
+            .filter(|edge| edge.source().index() != 0)
+            .map(|edge| {
+                (
+                    format!("n{}", edge.source().index()),
+                    format!("n{}", edge.target().index()),
+                    String::from("dependsOn"),
+                )
+            })
+            .collect();
+
+        (nodes, edges)
+    }
+
+    /// Validate a dependency between two projects against the
+    /// configured constraints, failing on the offending edge.
+    fn enforce_constraints(
+        &self,
+        project: &Project,
+        dependency: &Project,
+    ) -> Result<(), ProjectError> {
+        if self.constraints.enforce_project_type_relationships {
+            enforce_project_type_relationships(project, dependency)?;
+        }
+
+        if !self.constraints.tag_relationships.is_empty() {
+            enforce_tag_relationships(project, dependency, &self.constraints.tag_relationships)?;
+        }
+
+        Ok(())
+    }
+
     /// Internal method for lazily loading a project and its
     /// dependencies into the graph.
     fn internal_load(
@@ -285,6 +561,11 @@ impl ProjectGraph {
             &self.workspace_root,
             &self.global_config,
             &self.implicit_inputs,
+            self.implicit_depends_on
+                .get(id)
+                .map(|deps| deps.as_slice())
+                .unwrap_or_default(),
+            self.package_scripts.get(id),
         )?;
         let depends_on = project.get_dependencies();
 
@@ -303,6 +584,9 @@ impl ProjectGraph {
 
             for dep_id in depends_on {
                 let dep_index = self.internal_load(dep_id.as_str(), indices, graph)?;
+
+                self.enforce_constraints(&graph[node_index], &graph[dep_index])?;
+
                 graph.add_edge(node_index, dep_index, ());
             }
         }
@@ -0,0 +1,864 @@
+use moon_config::{ProjectWorkspaceInferredTasksConfig, TaskConfig, TaskID, TaskOptionsConfig};
+use moon_lang_node::package::ScriptsSet;
+use moon_logger::{color, debug, trace, warn};
+use moon_utils::process::split_args;
+use moon_utils::regex::matches_id;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A script that has been parsed into a command, and the scripts it runs beforehand.
+#[derive(Debug, Default, PartialEq)]
+struct ParsedScript {
+    args: Vec<String>,
+
+    deps: Vec<String>,
+
+    env: HashMap<String, String>,
+}
+
+/// Convert a script name into a task ID. Script names commonly contain colons
+/// (`build:types`), which are not supported in task IDs, so are replaced with dashes.
+fn convert_script_name(name: &str) -> TaskID {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// Split a script on `&&` into a list of commands, where each command is a list
+/// of arguments. Returns `None` if the script relies on other shell features
+/// (pipes, redirects, subshells, etc), as they can't be represented by a task.
+fn split_script(script: &str) -> Option<Vec<Vec<String>>> {
+    let mut commands = vec![];
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = script.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => {
+                quote = None;
+                current.push(c);
+            }
+            (Some('"') | None, '\\') => {
+                current.push(c);
+
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            (Some(_), _) => {
+                current.push(c);
+            }
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, '&') if chars.peek() == Some(&'&') => {
+                chars.next();
+                commands.push(std::mem::take(&mut current));
+            }
+            (None, '$') if chars.peek() == Some(&'(') => {
+                return None;
+            }
+            (None, '&' | '|' | ';' | '<' | '>' | '`') => {
+                return None;
+            }
+            _ => {
+                current.push(c);
+            }
+        }
+    }
+
+    commands.push(current);
+
+    commands
+        .iter()
+        .map(|command| match split_args(command) {
+            Ok(args) if !args.is_empty() => Some(args),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Return the name of the script being ran, if the arguments only run another script.
+/// For example: `npm run build`, `pnpm run build`, `yarn run build`, or `yarn build`.
+fn extract_script_reference(args: &[String]) -> Option<&str> {
+    match args.iter().map(|arg| arg.as_str()).collect::<Vec<_>>()[..] {
+        ["npm", "run" | "run-script", name] | ["pnpm" | "yarn", "run", name] | ["yarn", name] => {
+            Some(name)
+        }
+        _ => None,
+    }
+}
+
+/// Return the name and value if the argument is an environment variable
+/// assignment, for example `NODE_ENV=production`.
+fn extract_env_var(arg: &str) -> Option<(String, String)> {
+    let (key, value) = arg.split_once('=')?;
+
+    if key.is_empty()
+        || key.starts_with(|c: char| c.is_ascii_digit())
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return None;
+    }
+
+    Some((key.to_owned(), value.to_owned()))
+}
+
+/// Parse a script into a single command, while extracting other scripts that are ran
+/// before the command as dependencies. Returns the reason when the script can't be parsed.
+fn parse_script(
+    name: &str,
+    script: &str,
+    candidates: &BTreeMap<String, TaskID>,
+) -> Result<ParsedScript, &'static str> {
+    let mut parsed = ParsedScript::default();
+    let mut command: Option<Vec<String>> = None;
+
+    for mut args in split_script(script).ok_or("uses unsupported shell syntax")? {
+        if let Some(dep) = extract_script_reference(&args) {
+            if dep == name {
+                return Err("runs itself");
+            }
+
+            if candidates.contains_key(dep) {
+                if command.is_some() {
+                    return Err("runs another script after its command");
+                }
+
+                if !parsed.deps.iter().any(|d| d == dep) {
+                    parsed.deps.push(dep.to_owned());
+                }
+
+                continue;
+            }
+        }
+
+        if command.is_some() {
+            return Err("runs multiple commands");
+        }
+
+        while let Some((key, value)) = args.first().and_then(|arg| extract_env_var(arg)) {
+            parsed.env.insert(key, value);
+            args.remove(0);
+        }
+
+        if args.is_empty() {
+            return Err("only sets environment variables");
+        }
+
+        command = Some(args);
+    }
+
+    parsed.args = command.ok_or("only runs other scripts")?;
+
+    Ok(parsed)
+}
+
+/// Return the ID of the task that runs the command of a script with a `post` script.
+fn get_main_task_id(task_id: &str) -> TaskID {
+    format!("{}-main", task_id)
+}
+
+/// Return the name of the script whose task runs a script. A `post` script is ran as
+/// part of its script's task, so referencing `postbuild` runs the `build` task.
+fn get_owner_script<'a, T>(name: &'a str, scripts: &BTreeMap<String, T>) -> &'a str {
+    match name.strip_prefix("post") {
+        Some(main_name) if scripts.contains_key(main_name) => main_name,
+        _ => name,
+    }
+}
+
+/// Return the names of the scripts that a script runs: its `pre` script, the scripts
+/// it references, and its `post` script (which runs after, but is part of the same task).
+fn get_script_deps(
+    name: &str,
+    parsed: &ParsedScript,
+    scripts: &BTreeMap<String, ParsedScript>,
+) -> Vec<String> {
+    let mut deps = vec![];
+    let pre_name = format!("pre{}", name);
+    let post_name = format!("post{}", name);
+
+    if scripts.contains_key(&pre_name) {
+        deps.push(pre_name);
+    }
+
+    for dep in &parsed.deps {
+        deps.push(get_owner_script(dep, scripts).to_owned());
+    }
+
+    if scripts.contains_key(&post_name) {
+        deps.push(post_name);
+    }
+
+    deps
+}
+
+/// Return the names of scripts that run themselves through other scripts, for example,
+/// a `prebuild` script that runs `npm run build`, as their tasks would depend on each other.
+fn find_cyclic_scripts(scripts: &BTreeMap<String, ParsedScript>) -> Vec<String> {
+    let mut cyclic = vec![];
+
+    for (name, parsed) in scripts {
+        let mut visited = HashSet::new();
+        let mut queue = get_script_deps(name, parsed, scripts);
+
+        while let Some(dep) = queue.pop() {
+            if dep == *name {
+                cyclic.push(name.to_owned());
+                break;
+            }
+
+            if visited.insert(dep.clone()) {
+                queue.extend(get_script_deps(&dep, &scripts[&dep], scripts));
+            }
+        }
+    }
+
+    cyclic
+}
+
+/// Map the `pre` script and referenced scripts of a script to task dependencies.
+fn create_task_deps(
+    name: &str,
+    parsed: &ParsedScript,
+    candidates: &BTreeMap<String, TaskID>,
+) -> Vec<String> {
+    let mut deps = vec![];
+
+    // Dont use a `HashSet` as we want to preserve order
+    let mut push_dep = |task_id: &TaskID| {
+        let dep = format!("~:{}", task_id);
+
+        if !deps.contains(&dep) {
+            deps.push(dep);
+        }
+    };
+
+    // `prebuild` runs before `build`
+    if let Some(pre_task_id) = candidates.get(&format!("pre{}", name)) {
+        push_dep(pre_task_id);
+    }
+
+    for dep in &parsed.deps {
+        push_dep(&candidates[get_owner_script(dep, candidates)]);
+    }
+
+    deps
+}
+
+fn create_task_config(parsed: &ParsedScript, deps: Vec<String>) -> TaskConfig {
+    let mut args = parsed.args.clone();
+    let command = args.remove(0);
+
+    TaskConfig {
+        args: if args.is_empty() { None } else { Some(args) },
+        command: Some(command),
+        deps: if deps.is_empty() { None } else { Some(deps) },
+        env: if parsed.env.is_empty() {
+            None
+        } else {
+            Some(parsed.env.clone())
+        },
+        options: TaskOptionsConfig::default(),
+        ..TaskConfig::default()
+    }
+}
+
+/// Convert the scripts of a `package.json` into task configs, keyed by task ID.
+/// Scripts that run other scripts (`npm run`, `yarn`, etc), or have a `pre` script,
+/// will depend on those scripts' tasks. Scripts with a `post` script will run it afterwards.
+pub fn create_tasks_from_scripts(
+    log_target: &str,
+    scripts: &ScriptsSet,
+    config: &ProjectWorkspaceInferredTasksConfig,
+) -> HashMap<TaskID, TaskConfig> {
+    let mut tasks = HashMap::new();
+
+    debug!(
+        target: log_target,
+        "Inferring tasks from {} scripts",
+        color::file("package.json")
+    );
+
+    // Gather the scripts to convert while taking the include/exclude config into account
+    let mut candidates: BTreeMap<String, TaskID> = BTreeMap::new();
+
+    for name in scripts.keys() {
+        // None = Include all
+        // [] = Include none
+        // ["a"] = Include "a"
+        if let Some(include) = &config.include {
+            if !include.contains(name) {
+                trace!(
+                    target: log_target,
+                    "Not inferring task from script {}, not explicitly included",
+                    color::id(name)
+                );
+
+                continue;
+            }
+        }
+
+        // None, [] = Exclude none
+        // ["a"] = Exclude "a"
+        if let Some(exclude) = &config.exclude {
+            if exclude.contains(name) {
+                trace!(
+                    target: log_target,
+                    "Not inferring task from script {}, explicitly excluded",
+                    color::id(name)
+                );
+
+                continue;
+            }
+        }
+
+        let task_id = convert_script_name(name);
+
+        if !matches_id(&task_id) {
+            warn!(
+                target: log_target,
+                "Unable to infer a task from script {}, as {} is not a valid task ID",
+                color::id(name),
+                color::id(&task_id),
+            );
+
+            continue;
+        }
+
+        if let Some((existing_name, _)) = candidates.iter().find(|(_, id)| **id == task_id) {
+            warn!(
+                target: log_target,
+                "Unable to infer a task from script {}, as script {} already maps to task {}",
+                color::id(name),
+                color::id(existing_name),
+                color::id(&task_id),
+            );
+
+            continue;
+        }
+
+        candidates.insert(name.to_owned(), task_id);
+    }
+
+    // Parse the scripts and remove those that can't be converted. Since the remaining
+    // scripts may run the removed scripts, repeat until every remaining script parses.
+    let mut parsed_scripts = BTreeMap::new();
+
+    loop {
+        let mut failed = vec![];
+
+        parsed_scripts.clear();
+
+        for name in candidates.keys() {
+            match parse_script(name, &scripts[name], &candidates) {
+                Ok(parsed) => {
+                    parsed_scripts.insert(name.to_owned(), parsed);
+                }
+                Err(reason) => {
+                    failed.push((name.to_owned(), reason.to_owned()));
+                }
+            };
+        }
+
+        // The command of a script with a `post` script is moved to its own task
+        if failed.is_empty() {
+            for (name, task_id) in &candidates {
+                let post_name = format!("post{}", name);
+                let main_task_id = get_main_task_id(task_id);
+
+                if candidates.contains_key(&post_name)
+                    && candidates.values().any(|id| *id == main_task_id)
+                {
+                    failed.push((
+                        post_name,
+                        format!("conflicts with task {}", color::id(&main_task_id)),
+                    ));
+                }
+            }
+        }
+
+        if failed.is_empty() {
+            for name in find_cyclic_scripts(&parsed_scripts) {
+                failed.push((name, "depends on itself through other scripts".to_owned()));
+            }
+        }
+
+        if failed.is_empty() {
+            break;
+        }
+
+        for (name, reason) in failed {
+            warn!(
+                target: log_target,
+                "Unable to infer a task from script {}, as it {}",
+                color::id(&name),
+                reason,
+            );
+
+            candidates.remove(&name);
+        }
+    }
+
+    for (name, parsed) in &parsed_scripts {
+        // `postbuild` is ran as part of the `build` task below
+        if get_owner_script(name, &parsed_scripts) != name {
+            continue;
+        }
+
+        let task_id = candidates[name].clone();
+        let deps = create_task_deps(name, parsed, &candidates);
+        let post_name = format!("post{}", name);
+
+        trace!(
+            target: log_target,
+            "Inferring task {} from script {}",
+            color::id(&task_id),
+            color::id(name),
+        );
+
+        // `build` runs `postbuild` after its own command, so the command is moved to
+        // a `build-main` task, and the `build` task runs `postbuild` once it completes
+        if let Some(post_parsed) = parsed_scripts.get(&post_name) {
+            let main_task_id = get_main_task_id(&task_id);
+            let mut post_deps = vec![format!("~:{}", main_task_id)];
+
+            for dep in create_task_deps(&post_name, post_parsed, &candidates) {
+                if !post_deps.contains(&dep) {
+                    post_deps.push(dep);
+                }
+            }
+
+            trace!(
+                target: log_target,
+                "Inferring task {} from script {}, and running script {} after it",
+                color::id(&main_task_id),
+                color::id(name),
+                color::id(&post_name),
+            );
+
+            tasks.insert(main_task_id, create_task_config(parsed, deps));
+            tasks.insert(task_id, create_task_config(post_parsed, post_deps));
+        } else {
+            tasks.insert(task_id, create_task_config(parsed, deps));
+        }
+    }
+
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use moon_utils::string_vec;
+
+    fn create_tasks(
+        scripts: &[(&str, &str)],
+        config: ProjectWorkspaceInferredTasksConfig,
+    ) -> HashMap<TaskID, TaskConfig> {
+        create_tasks_from_scripts(
+            "moon:project:test",
+            &ScriptsSet::from_iter(
+                scripts
+                    .iter()
+                    .map(|(name, script)| (name.to_string(), script.to_string())),
+            ),
+            &config,
+        )
+    }
+
+    fn create_all_tasks(scripts: &[(&str, &str)]) -> HashMap<TaskID, TaskConfig> {
+        create_tasks(scripts, ProjectWorkspaceInferredTasksConfig::default())
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn splits_command_and_args() {
+            let tasks = create_all_tasks(&[("lint", "eslint --ext .ts 'src/**/*'")]);
+            let task = tasks.get("lint").unwrap();
+
+            assert_eq!(task.command, Some("eslint".to_owned()));
+            assert_eq!(task.args, Some(string_vec!["--ext", ".ts", "src/**/*"]));
+            assert_eq!(task.deps, None);
+        }
+
+        #[test]
+        fn extracts_env_vars() {
+            let tasks = create_all_tasks(&[("build", "NODE_ENV=production BABEL_ENV=esm babel")]);
+            let task = tasks.get("build").unwrap();
+
+            assert_eq!(task.command, Some("babel".to_owned()));
+            assert_eq!(task.args, None);
+            assert_eq!(
+                task.env,
+                Some(HashMap::from([
+                    ("NODE_ENV".to_owned(), "production".to_owned()),
+                    ("BABEL_ENV".to_owned(), "esm".to_owned())
+                ]))
+            );
+        }
+
+        #[test]
+        fn converts_script_names_to_task_ids() {
+            let tasks = create_all_tasks(&[("build:types", "tsc")]);
+
+            assert!(tasks.contains_key("build-types"));
+        }
+
+        #[test]
+        fn skips_invalid_task_ids() {
+            let tasks = create_all_tasks(&[("1build", "tsc"), ("_test", "jest")]);
+
+            assert!(tasks.is_empty());
+        }
+
+        #[test]
+        fn skips_unsupported_shell_syntax() {
+            let tasks = create_all_tasks(&[
+                ("pipe", "cat file | grep foo"),
+                ("or", "jest || true"),
+                ("redirect", "tsc > out.log"),
+                ("subshell", "echo $(date)"),
+                ("background", "tsc &"),
+                ("sequence", "tsc; jest"),
+                ("quoted", "echo 'a | b > c'"),
+            ]);
+
+            assert_eq!(tasks.keys().collect::<Vec<_>>(), vec!["quoted"]);
+            assert_eq!(
+                tasks.get("quoted").unwrap().args,
+                Some(string_vec!["a | b > c"])
+            );
+        }
+    }
+
+    mod script_references {
+        use super::*;
+
+        #[test]
+        fn converts_leading_references_to_deps() {
+            let tasks = create_all_tasks(&[
+                (
+                    "build",
+                    "npm run clean && yarn types && pnpm run copy && tsc",
+                ),
+                ("clean", "rimraf lib"),
+                ("copy", "copyfiles src lib"),
+                ("types", "tsc --emitDeclarationOnly"),
+            ]);
+            let task = tasks.get("build").unwrap();
+
+            assert_eq!(task.command, Some("tsc".to_owned()));
+            assert_eq!(task.args, None);
+            assert_eq!(task.deps, Some(string_vec!["~:clean", "~:types", "~:copy"]));
+        }
+
+        #[test]
+        fn maps_references_to_converted_task_ids() {
+            let tasks = create_all_tasks(&[
+                ("build", "yarn run build:types && tsc"),
+                ("build:types", "tsc --emitDeclarationOnly"),
+            ]);
+
+            assert_eq!(
+                tasks.get("build").unwrap().deps,
+                Some(string_vec!["~:build-types"])
+            );
+        }
+
+        #[test]
+        fn keeps_references_to_unknown_scripts_as_commands() {
+            let tasks = create_all_tasks(&[("test", "npm run jest -- --coverage")]);
+            let task = tasks.get("test").unwrap();
+
+            assert_eq!(task.command, Some("npm".to_owned()));
+            assert_eq!(
+                task.args,
+                Some(string_vec!["run", "jest", "--", "--coverage"])
+            );
+            assert_eq!(task.deps, None);
+        }
+
+        #[test]
+        fn keeps_references_to_excluded_scripts_as_commands() {
+            let tasks = create_tasks(
+                &[("build", "npm run clean && tsc"), ("clean", "rimraf lib")],
+                ProjectWorkspaceInferredTasksConfig {
+                    exclude: Some(string_vec!["clean"]),
+                    ..ProjectWorkspaceInferredTasksConfig::default()
+                },
+            );
+
+            // Runs 2 commands, so can't be converted
+            assert!(tasks.is_empty());
+
+            let tasks = create_tasks(
+                &[("build", "npm run clean"), ("clean", "rimraf lib")],
+                ProjectWorkspaceInferredTasksConfig {
+                    exclude: Some(string_vec!["clean"]),
+                    ..ProjectWorkspaceInferredTasksConfig::default()
+                },
+            );
+            let task = tasks.get("build").unwrap();
+
+            assert_eq!(task.command, Some("npm".to_owned()));
+            assert_eq!(task.args, Some(string_vec!["run", "clean"]));
+        }
+
+        #[test]
+        fn skips_references_after_command() {
+            let tasks = create_all_tasks(&[("build", "tsc && npm run copy"), ("copy", "cp a b")]);
+
+            assert!(!tasks.contains_key("build"));
+            assert!(tasks.contains_key("copy"));
+        }
+
+        #[test]
+        fn skips_scripts_that_only_run_other_scripts() {
+            let tasks = create_all_tasks(&[
+                ("build", "npm run build:cjs && npm run build:esm"),
+                ("build:cjs", "babel --env-name cjs"),
+                ("build:esm", "babel --env-name esm"),
+                ("release", "npm run build && npm publish"),
+            ]);
+
+            assert!(!tasks.contains_key("build"));
+
+            // `build` was skipped, so is ran as a command instead,
+            // which results in multiple commands
+            assert!(!tasks.contains_key("release"));
+        }
+
+        #[test]
+        fn skips_self_references() {
+            let tasks = create_all_tasks(&[("build", "yarn build && tsc")]);
+
+            assert!(tasks.is_empty());
+        }
+    }
+
+    mod pre_post {
+        use super::*;
+
+        #[test]
+        fn depends_on_pre_script() {
+            let tasks = create_all_tasks(&[
+                ("build", "npm run types && tsc"),
+                ("prebuild", "rimraf lib"),
+                ("types", "tsc --emitDeclarationOnly"),
+            ]);
+
+            assert_eq!(
+                tasks.get("build").unwrap().deps,
+                Some(string_vec!["~:prebuild", "~:types"])
+            );
+            assert_eq!(tasks.get("prebuild").unwrap().deps, None);
+        }
+
+        #[test]
+        fn runs_post_script_after_script() {
+            let tasks = create_all_tasks(&[
+                ("pretest", "rimraf coverage"),
+                ("test", "jest"),
+                ("posttest", "codecov"),
+            ]);
+
+            let main = tasks.get("test-main").unwrap();
+
+            assert_eq!(main.command, Some("jest".to_owned()));
+            assert_eq!(main.deps, Some(string_vec!["~:pretest"]));
+
+            // Running `test` runs `posttest`, once the script itself has ran
+            let test = tasks.get("test").unwrap();
+
+            assert_eq!(test.command, Some("codecov".to_owned()));
+            assert_eq!(test.deps, Some(string_vec!["~:test-main"]));
+
+            assert!(!tasks.contains_key("posttest"));
+        }
+
+        #[test]
+        fn runs_post_script_references_after_script() {
+            let tasks = create_all_tasks(&[
+                ("build", "tsc"),
+                ("postbuild", "npm run copy && prettier --write lib"),
+                ("copy", "copyfiles src lib"),
+            ]);
+
+            assert_eq!(
+                tasks.get("build").unwrap().deps,
+                Some(string_vec!["~:build-main", "~:copy"])
+            );
+            assert_eq!(tasks.get("build-main").unwrap().deps, None);
+        }
+
+        #[test]
+        fn skips_post_script_that_conflicts_with_main_task() {
+            let tasks = create_all_tasks(&[
+                ("build", "tsc"),
+                ("build:main", "babel"),
+                ("postbuild", "codecov"),
+            ]);
+
+            assert_eq!(tasks.get("build").unwrap().command, Some("tsc".to_owned()));
+            assert_eq!(
+                tasks.get("build-main").unwrap().command,
+                Some("babel".to_owned())
+            );
+            assert!(!tasks.contains_key("postbuild"));
+        }
+
+        #[test]
+        fn maps_post_script_references_to_script_task() {
+            let tasks = create_all_tasks(&[
+                ("build", "tsc"),
+                ("postbuild", "codecov"),
+                ("ci", "npm run postbuild && eslint ."),
+            ]);
+
+            assert_eq!(tasks.get("ci").unwrap().deps, Some(string_vec!["~:build"]));
+            assert!(!tasks.contains_key("postbuild"));
+        }
+
+        #[test]
+        fn skips_script_that_runs_its_post_script() {
+            let tasks = create_all_tasks(&[
+                ("build", "npm run postbuild && tsc"),
+                ("postbuild", "codecov"),
+            ]);
+
+            assert!(!tasks.contains_key("build"));
+            assert!(!tasks.contains_key("build-main"));
+            assert_eq!(
+                tasks.get("postbuild").unwrap().command,
+                Some("codecov".to_owned())
+            );
+        }
+
+        #[test]
+        fn skips_pre_script_that_runs_script() {
+            let tasks = create_all_tasks(&[
+                ("build", "tsc"),
+                ("prebuild", "npm run build && rimraf lib"),
+                ("lint", "eslint ."),
+            ]);
+
+            assert!(!tasks.contains_key("build"));
+            assert!(!tasks.contains_key("prebuild"));
+            assert!(tasks.contains_key("lint"));
+        }
+
+        #[test]
+        fn skips_post_script_that_runs_script() {
+            let tasks = create_all_tasks(&[("test", "jest"), ("posttest", "yarn test && codecov")]);
+
+            assert!(tasks.is_empty());
+        }
+
+        #[test]
+        fn skips_cycles_through_references() {
+            let tasks = create_all_tasks(&[
+                ("a", "npm run b && tsc"),
+                ("b", "npm run c && tsc"),
+                ("c", "npm run a && tsc"),
+                ("d", "npm run e && tsc"),
+                ("e", "tsc"),
+            ]);
+
+            let mut task_ids = tasks.keys().collect::<Vec<_>>();
+            task_ids.sort();
+
+            assert_eq!(task_ids, vec!["d", "e"]);
+        }
+
+        #[test]
+        fn ignores_prefixes_without_script() {
+            let tasks =
+                create_all_tasks(&[("prettier", "prettier --check ."), ("postinstall", "husky")]);
+
+            assert_eq!(tasks.get("prettier").unwrap().deps, None);
+            assert_eq!(tasks.get("postinstall").unwrap().deps, None);
+        }
+
+        #[test]
+        fn ignores_excluded_pre_scripts() {
+            let tasks = create_tasks(
+                &[("build", "tsc"), ("prebuild", "rimraf lib")],
+                ProjectWorkspaceInferredTasksConfig {
+                    exclude: Some(string_vec!["prebuild"]),
+                    ..ProjectWorkspaceInferredTasksConfig::default()
+                },
+            );
+
+            assert_eq!(tasks.get("build").unwrap().deps, None);
+            assert!(!tasks.contains_key("prebuild"));
+        }
+    }
+
+    mod include_exclude {
+        use super::*;
+
+        const SCRIPTS: [(&str, &str); 3] =
+            [("build", "tsc"), ("lint", "eslint ."), ("test", "jest")];
+
+        #[test]
+        fn includes_all_by_default() {
+            let tasks = create_all_tasks(&SCRIPTS);
+
+            assert_eq!(tasks.len(), 3);
+        }
+
+        #[test]
+        fn includes_none_when_empty() {
+            let tasks = create_tasks(
+                &SCRIPTS,
+                ProjectWorkspaceInferredTasksConfig {
+                    include: Some(vec![]),
+                    ..ProjectWorkspaceInferredTasksConfig::default()
+                },
+            );
+
+            assert!(tasks.is_empty());
+        }
+
+        #[test]
+        fn includes_explicit() {
+            let tasks = create_tasks(
+                &SCRIPTS,
+                ProjectWorkspaceInferredTasksConfig {
+                    include: Some(string_vec!["build", "test"]),
+                    ..ProjectWorkspaceInferredTasksConfig::default()
+                },
+            );
+
+            assert!(tasks.contains_key("build"));
+            assert!(!tasks.contains_key("lint"));
+            assert!(tasks.contains_key("test"));
+        }
+
+        #[test]
+        fn excludes_explicit() {
+            let tasks = create_tasks(
+                &SCRIPTS,
+                ProjectWorkspaceInferredTasksConfig {
+                    exclude: Some(string_vec!["lint"]),
+                    include: Some(string_vec!["build", "lint"]),
+                },
+            );
+
+            assert_eq!(tasks.keys().collect::<Vec<_>>(), vec!["build"]);
+        }
+    }
+}
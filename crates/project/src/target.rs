@@ -10,6 +10,7 @@ pub enum TargetProject {
     Deps,          // ^:task
     Id(ProjectID), // project:task
     Own,           // ~:task
+    Tag(String),   // #tag:task
 }
 
 // impl fmt::Display for TargetProject {
@@ -84,6 +85,7 @@ impl Target {
                 "" => TargetProject::All,
                 "^" => TargetProject::Deps,
                 "~" => TargetProject::Own,
+                tag if tag.starts_with('#') => TargetProject::Tag(tag[1..].to_owned()),
                 id => {
                     project_id = Some(id.to_owned());
                     TargetProject::Id(id.to_owned())
@@ -233,6 +235,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_tag_projects() {
+        assert_eq!(
+            Target::parse("#frontend:build").unwrap(),
+            Target {
+                id: String::from("#frontend:build"),
+                project: TargetProject::Tag("frontend".to_owned()),
+                project_id: None,
+                task_id: "build".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_namespaced_tag_projects() {
+        assert_eq!(
+            Target::parse("#scope:billing:build").unwrap(),
+            Target {
+                id: String::from("#scope:billing:build"),
+                project: TargetProject::Tag("scope:billing".to_owned()),
+                project_id: None,
+                task_id: "build".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Target(IdOnly(\"#frontend:build\"))")]
+    fn tag_projects_have_no_ids() {
+        Target::parse("#frontend:build").unwrap().ids().unwrap();
+    }
+
     // #[test]
     // fn parse_all_tasks() {
     //     assert_eq!(
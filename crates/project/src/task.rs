@@ -6,7 +6,7 @@ use moon_config::{
     FilePath, FilePathOrGlob, TargetID, TaskConfig, TaskMergeStrategy, TaskOptionsConfig, TaskType,
 };
 use moon_logger::{color, debug, map_list, trace, Logable};
-use moon_utils::{glob, path, string_vec};
+use moon_utils::{glob, path, regex, string_vec};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
@@ -23,11 +23,17 @@ pub struct TaskOptions {
 
     pub merge_outputs: TaskMergeStrategy,
 
+    /// Never completes (dev servers, watchers), so is never hashed or cached.
+    pub persistent: bool,
+
     pub retry_count: u8,
 
     pub run_in_ci: bool,
 
     pub run_from_workspace_root: bool,
+
+    /// Maximum amount of seconds an attempt may run before being terminated.
+    pub timeout: Option<u64>,
 }
 
 impl TaskOptions {
@@ -52,6 +58,10 @@ impl TaskOptions {
             self.merge_outputs = merge_outputs.clone();
         }
 
+        if let Some(persistent) = &config.persistent {
+            self.persistent = *persistent;
+        }
+
         if let Some(retry_count) = &config.retry_count {
             self.retry_count = *retry_count;
         }
@@ -63,6 +73,10 @@ impl TaskOptions {
         if let Some(run_from_workspace_root) = &config.run_from_workspace_root {
             self.run_from_workspace_root = *run_from_workspace_root;
         }
+
+        if let Some(timeout) = &config.timeout {
+            self.timeout = Some(*timeout);
+        }
     }
 }
 
@@ -83,6 +97,8 @@ pub struct Task {
 
     pub input_paths: ExpandedFiles,
 
+    pub input_vars: HashSet<String>,
+
     #[serde(skip)]
     pub log_target: String,
 
@@ -110,6 +126,7 @@ impl Task {
         let cloned_options = cloned_config.options;
         let command = cloned_config.command.unwrap_or_default();
         let is_long_running = command == "dev" || command == "serve" || command == "start";
+        let is_persistent = cloned_options.persistent.unwrap_or_default();
         let log_target = format!("moon:project:{}", target);
 
         let task = Task {
@@ -120,6 +137,7 @@ impl Task {
             inputs: cloned_config.inputs.unwrap_or_else(|| string_vec!["**/*"]),
             input_globs: vec![],
             input_paths: HashSet::new(),
+            input_vars: HashSet::new(),
             log_target,
             options: TaskOptions {
                 merge_args: cloned_options.merge_args.unwrap_or_default(),
@@ -127,9 +145,13 @@ impl Task {
                 merge_env: cloned_options.merge_env.unwrap_or_default(),
                 merge_inputs: cloned_options.merge_inputs.unwrap_or_default(),
                 merge_outputs: cloned_options.merge_outputs.unwrap_or_default(),
+                persistent: is_persistent,
                 retry_count: cloned_options.retry_count.unwrap_or_default(),
-                run_in_ci: cloned_options.run_in_ci.unwrap_or(!is_long_running),
+                run_in_ci: cloned_options
+                    .run_in_ci
+                    .unwrap_or(!(is_long_running || is_persistent)),
                 run_from_workspace_root: cloned_options.run_from_workspace_root.unwrap_or_default(),
+                timeout: cloned_options.timeout,
             },
             outputs: cloned_config.outputs.unwrap_or_default(),
             output_paths: HashSet::new(),
@@ -240,6 +262,10 @@ impl Task {
                 TargetProject::Id(_) => {
                     push_dep(dep.clone());
                 }
+                // #tag:task
+                TargetProject::Tag(_) => {
+                    target.fail_with(TargetError::NoProjectTagInTaskDeps(target.id.clone()))?;
+                }
                 _ => {
                     target.fail_with(TargetError::NoProjectAllInTaskDeps(target.id.clone()))?;
                 }
@@ -252,12 +278,23 @@ impl Task {
     }
 
     /// Expand the inputs list to a set of absolute file paths, while resolving tokens.
+    /// Inputs in the format of `$NAME` are environment variables, and are not expanded.
     pub fn expand_inputs(&mut self, token_resolver: TokenResolver) -> Result<(), ProjectError> {
         if self.inputs.is_empty() {
             return Ok(());
         }
 
-        for input in &token_resolver.resolve(&self.inputs, self)? {
+        let mut file_inputs = vec![];
+
+        for input in &self.inputs {
+            if regex::matches_env_var(input) {
+                self.input_vars.insert(input[1..].to_owned());
+            } else {
+                file_inputs.push(input.clone());
+            }
+        }
+
+        for input in &token_resolver.resolve(&file_inputs, self)? {
             // We cant canonicalize here as these inputs may not exist!
             if glob::is_path_glob(input) {
                 self.input_globs.push(glob::normalize(input)?);
@@ -539,4 +576,47 @@ mod tests {
             assert!(!task.is_affected(&set).unwrap());
         }
     }
+
+    mod persistent {
+        use crate::task::Task;
+        use moon_config::{TaskConfig, TaskOptionsConfig};
+
+        #[test]
+        fn disables_run_in_ci_by_default() {
+            let task = Task::from_config(
+                String::from("project:serve"),
+                &TaskConfig {
+                    command: Some(String::from("webpack")),
+                    options: TaskOptionsConfig {
+                        persistent: Some(true),
+                        run_in_ci: None,
+                        ..TaskOptionsConfig::default()
+                    },
+                    ..TaskConfig::default()
+                },
+            );
+
+            assert!(task.options.persistent);
+            assert!(!task.should_run_in_ci());
+        }
+
+        #[test]
+        fn disabled_by_default_for_long_running_commands() {
+            let task = Task::from_config(
+                String::from("project:dev"),
+                &TaskConfig {
+                    command: Some(String::from("dev")),
+                    options: TaskOptionsConfig {
+                        persistent: None,
+                        run_in_ci: None,
+                        ..TaskOptionsConfig::default()
+                    },
+                    ..TaskConfig::default()
+                },
+            );
+
+            assert!(!task.options.persistent);
+            assert!(!task.should_run_in_ci());
+        }
+    }
 }
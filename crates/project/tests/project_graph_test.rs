@@ -1,6 +1,6 @@
 use insta::assert_snapshot;
 use moon_cache::CacheEngine;
-use moon_config::{GlobalProjectConfig, WorkspaceConfig};
+use moon_config::{ConstraintsConfig, GlobalProjectConfig, NodeConfig, WorkspaceConfig};
 use moon_project::ProjectGraph;
 use moon_utils::string_vec;
 use moon_utils::test::get_fixtures_dir;
@@ -50,6 +50,63 @@ async fn get_dependents_graph() -> ProjectGraph {
     .unwrap()
 }
 
+async fn get_constraints_graph(constraints: ConstraintsConfig) -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/constraints");
+    let workspace_config = WorkspaceConfig {
+        constraints,
+        projects: HashMap::from([
+            ("app".to_owned(), "app".to_owned()),
+            ("appToApp".to_owned(), "app-to-app".to_owned()),
+            ("billing".to_owned(), "billing".to_owned()),
+            (
+                "billingToUntagged".to_owned(),
+                "billing-to-untagged".to_owned(),
+            ),
+            ("lib".to_owned(), "lib".to_owned()),
+            ("libToApp".to_owned(), "lib-to-app".to_owned()),
+            ("libToTool".to_owned(), "lib-to-tool".to_owned()),
+            ("other".to_owned(), "other".to_owned()),
+            ("shared".to_owned(), "shared".to_owned()),
+            ("tool".to_owned(), "tool".to_owned()),
+            ("toolToApp".to_owned(), "tool-to-app".to_owned()),
+            ("untagged".to_owned(), "untagged".to_owned()),
+        ]),
+        ..WorkspaceConfig::default()
+    };
+
+    ProjectGraph::create(
+        &workspace_root,
+        &workspace_config,
+        GlobalProjectConfig::default(),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+async fn get_inferred_graph(node: NodeConfig) -> ProjectGraph {
+    let workspace_root = get_fixtures_dir("project-graph/inferred");
+    let workspace_config = WorkspaceConfig {
+        node,
+        projects: HashMap::from([
+            ("a".to_owned(), "a".to_owned()),
+            ("b".to_owned(), "b".to_owned()),
+            ("c".to_owned(), "c".to_owned()),
+            ("d".to_owned(), "d".to_owned()),
+        ]),
+        ..WorkspaceConfig::default()
+    };
+
+    ProjectGraph::create(
+        &workspace_root,
+        &workspace_config,
+        GlobalProjectConfig::default(),
+        &CacheEngine::create(&workspace_root).await.unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
 mod get_dependencies_of {
     use super::*;
 
@@ -72,6 +129,137 @@ mod get_dependencies_of {
     }
 }
 
+mod infer_project_dependencies {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_uses_depends_on_by_default() {
+        let graph = get_inferred_graph(NodeConfig::default()).await;
+
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(graph.get_dependencies_of(&a).unwrap(), string_vec!["d"]);
+    }
+
+    #[tokio::test]
+    async fn merges_package_json_dependencies() {
+        let graph = get_inferred_graph(NodeConfig {
+            infer_project_dependencies: true,
+            ..NodeConfig::default()
+        })
+        .await;
+
+        let a = graph.load("a").unwrap();
+        let mut deps = graph.get_dependencies_of(&a).unwrap();
+        deps.sort();
+
+        assert_eq!(deps, string_vec!["b", "c", "d"]);
+        assert_eq!(a.config.depends_on, string_vec!["d", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn expands_deps_scope_with_inferred_dependencies() {
+        let graph = get_inferred_graph(NodeConfig {
+            infer_project_dependencies: true,
+            ..NodeConfig::default()
+        })
+        .await;
+
+        let a = graph.load("a").unwrap();
+
+        assert_eq!(
+            a.tasks.get("build").unwrap().deps,
+            string_vec!["d:build", "b:build", "c:build"]
+        );
+    }
+
+    #[tokio::test]
+    async fn infers_for_each_project() {
+        let graph = get_inferred_graph(NodeConfig {
+            infer_project_dependencies: true,
+            ..NodeConfig::default()
+        })
+        .await;
+
+        let c = graph.load("c").unwrap();
+
+        assert_eq!(graph.get_dependencies_of(&c).unwrap(), string_vec!["b"]);
+    }
+
+    #[tokio::test]
+    async fn doesnt_infer_duplicate_package_names() {
+        let workspace_root = get_fixtures_dir("project-graph/inferred");
+        let workspace_config = WorkspaceConfig {
+            node: NodeConfig {
+                infer_project_dependencies: true,
+                ..NodeConfig::default()
+            },
+            projects: HashMap::from([
+                ("b".to_owned(), "b".to_owned()),
+                ("c".to_owned(), "c".to_owned()),
+                ("duplicate".to_owned(), "duplicate".to_owned()),
+            ]),
+            ..WorkspaceConfig::default()
+        };
+
+        let graph = ProjectGraph::create(
+            &workspace_root,
+            &workspace_config,
+            GlobalProjectConfig::default(),
+            &CacheEngine::create(&workspace_root).await.unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let c = graph.load("c").unwrap();
+
+        assert_eq!(graph.get_dependencies_of(&c).unwrap(), string_vec![]);
+    }
+}
+
+mod infer_tasks_from_scripts {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_uses_configured_tasks_by_default() {
+        let graph = get_inferred_graph(NodeConfig::default()).await;
+
+        let b = graph.load("b").unwrap();
+
+        assert!(!b.tasks.contains_key("lint"));
+    }
+
+    #[tokio::test]
+    async fn creates_tasks_from_scripts() {
+        let graph = get_inferred_graph(NodeConfig {
+            infer_tasks_from_scripts: true,
+            ..NodeConfig::default()
+        })
+        .await;
+
+        let b = graph.load("b").unwrap();
+
+        assert_eq!(b.get_task("lint").unwrap().command, "eslint");
+        assert_eq!(b.get_task("lint").unwrap().args, string_vec!["."]);
+
+        // Local tasks take precedence
+        assert_eq!(b.get_task("build").unwrap().command, "webpack");
+    }
+
+    #[tokio::test]
+    async fn skips_projects_without_package_json() {
+        let graph = get_inferred_graph(NodeConfig {
+            infer_tasks_from_scripts: true,
+            ..NodeConfig::default()
+        })
+        .await;
+
+        let d = graph.load("d").unwrap();
+
+        assert_eq!(d.tasks.keys().collect::<Vec<_>>(), vec!["build"]);
+    }
+}
+
 mod get_dependents_of {
     use super::*;
 
@@ -94,6 +282,68 @@ mod get_dependents_of {
     }
 }
 
+mod find_project_id_from_path {
+    use super::*;
+
+    #[tokio::test]
+    async fn returns_project_for_nested_path() {
+        let graph = get_dependencies_graph().await;
+        let root = get_fixtures_dir("project-graph/dependencies");
+
+        assert_eq!(
+            graph.find_project_id_from_path(&root.join("b/src/index.ts")),
+            Some("b".to_owned())
+        );
+        assert_eq!(
+            graph.find_project_id_from_path(&root.join("c")),
+            Some("c".to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn returns_none_outside_of_projects() {
+        let graph = get_dependencies_graph().await;
+        let root = get_fixtures_dir("project-graph/dependencies");
+
+        assert_eq!(graph.find_project_id_from_path(&root), None);
+        assert_eq!(graph.find_project_id_from_path(&root.join("bar")), None);
+    }
+
+    #[tokio::test]
+    async fn returns_root_level_project() {
+        let workspace_root = get_fixtures_dir("project-graph/dependencies");
+        let workspace_config = WorkspaceConfig {
+            projects: HashMap::from([
+                ("root".to_owned(), ".".to_owned()),
+                ("b".to_owned(), "b".to_owned()),
+            ]),
+            ..WorkspaceConfig::default()
+        };
+
+        let graph = ProjectGraph::create(
+            &workspace_root,
+            &workspace_config,
+            GlobalProjectConfig::default(),
+            &CacheEngine::create(&workspace_root).await.unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            graph.find_project_id_from_path(&workspace_root),
+            Some("root".to_owned())
+        );
+        assert_eq!(
+            graph.find_project_id_from_path(&workspace_root.join("bar")),
+            Some("root".to_owned())
+        );
+        assert_eq!(
+            graph.find_project_id_from_path(&workspace_root.join("b/src/index.ts")),
+            Some("b".to_owned())
+        );
+    }
+}
+
 mod to_dot {
     use super::*;
 
@@ -109,3 +359,160 @@ mod to_dot {
         assert_snapshot!(graph.to_dot());
     }
 }
+
+mod to_graphml {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_tree() {
+        let graph = get_dependencies_graph().await;
+
+        graph.load("a").unwrap();
+        graph.load("b").unwrap();
+        graph.load("c").unwrap();
+        graph.load("d").unwrap();
+
+        assert_snapshot!(graph.to_graphml());
+    }
+}
+
+mod to_json {
+    use super::*;
+
+    #[tokio::test]
+    async fn sorts_nodes_and_edges() {
+        let graph = get_dependencies_graph().await;
+
+        graph.load("d").unwrap();
+        graph.load("a").unwrap();
+
+        let json = graph.to_json();
+
+        assert_eq!(json.version, 1);
+        assert_eq!(
+            json.nodes.iter().map(|p| p.id.clone()).collect::<Vec<_>>(),
+            string_vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            json.edges
+                .iter()
+                .map(|e| format!("{} -> {}", e.source, e.target))
+                .collect::<Vec<_>>(),
+            string_vec!["a -> b", "b -> c", "d -> a", "d -> b", "d -> c"]
+        );
+    }
+
+    #[tokio::test]
+    async fn excludes_root_node() {
+        let graph = get_dependencies_graph().await;
+
+        graph.load("c").unwrap();
+
+        let json = graph.to_json();
+
+        assert_eq!(json.nodes.len(), 1);
+        assert!(json.edges.is_empty());
+    }
+}
+
+mod to_mermaid {
+    use super::*;
+
+    #[tokio::test]
+    async fn renders_tree() {
+        let graph = get_dependencies_graph().await;
+
+        graph.load("a").unwrap();
+        graph.load("b").unwrap();
+        graph.load("c").unwrap();
+        graph.load("d").unwrap();
+
+        assert_snapshot!(graph.to_mermaid());
+    }
+}
+
+mod constraints {
+    use super::*;
+
+    fn type_constraints() -> ConstraintsConfig {
+        ConstraintsConfig {
+            enforce_project_type_relationships: true,
+            ..ConstraintsConfig::default()
+        }
+    }
+
+    fn tag_constraints() -> ConstraintsConfig {
+        ConstraintsConfig {
+            tag_relationships: HashMap::from([(
+                "scope:billing".to_owned(),
+                string_vec!["scope:shared"],
+            )]),
+            ..ConstraintsConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn ignores_invalid_relationships_by_default() {
+        let graph = get_constraints_graph(ConstraintsConfig::default()).await;
+
+        graph.load_all().unwrap();
+    }
+
+    #[tokio::test]
+    async fn allows_valid_type_relationships() {
+        let graph = get_constraints_graph(type_constraints()).await;
+
+        graph.load("app").unwrap();
+        graph.load("tool").unwrap();
+        graph.load("libToTool").unwrap();
+        graph.load("toolToApp").unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "InvalidTypeRelationship(\"appToApp\", \"app\", \"application\", \"application\")"
+    )]
+    async fn errors_for_app_depending_on_app() {
+        let graph = get_constraints_graph(type_constraints()).await;
+
+        graph.load("appToApp").unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "InvalidTypeRelationship(\"libToApp\", \"app\", \"library\", \"application\")"
+    )]
+    async fn errors_for_lib_depending_on_app() {
+        let graph = get_constraints_graph(type_constraints()).await;
+
+        graph.load("libToApp").unwrap();
+    }
+
+    #[tokio::test]
+    async fn allows_valid_tag_relationships() {
+        let graph = get_constraints_graph(tag_constraints()).await;
+
+        graph.load("lib").unwrap();
+        graph.load("other").unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "InvalidTagRelationship(\"billing\", \"other\", \"scope:billing\", \"scope:billing, scope:shared\")"
+    )]
+    async fn errors_for_dependency_without_allowed_tag() {
+        let graph = get_constraints_graph(tag_constraints()).await;
+
+        graph.load("billing").unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(
+        expected = "InvalidTagRelationship(\"billingToUntagged\", \"untagged\", \"scope:billing\", \"scope:billing, scope:shared\")"
+    )]
+    async fn errors_for_dependency_without_tags() {
+        let graph = get_constraints_graph(tag_constraints()).await;
+
+        graph.load("billingToUntagged").unwrap();
+    }
+}
@@ -33,6 +33,8 @@ fn doesnt_exist() {
         &get_fixtures_root(),
         &mock_global_project_config(),
         &[],
+        &[],
+        None,
     )
     .unwrap();
 }
@@ -46,6 +48,8 @@ fn no_config() {
         &workspace_root,
         &mock_global_project_config(),
         &[],
+        &[],
+        None,
     )
     .unwrap();
 
@@ -71,6 +75,8 @@ fn empty_config() {
         &workspace_root,
         &mock_global_project_config(),
         &[],
+        &[],
+        None,
     )
     .unwrap();
 
@@ -97,6 +103,8 @@ fn basic_config() {
         &workspace_root,
         &mock_global_project_config(),
         &[],
+        &[],
+        None,
     )
     .unwrap();
     let project_root = workspace_root.join("projects/basic");
@@ -136,6 +144,8 @@ fn advanced_config() {
         &workspace_root,
         &mock_global_project_config(),
         &[],
+        &[],
+        None,
     )
     .unwrap();
 
@@ -176,6 +186,8 @@ fn overrides_global_file_groups() {
             ..GlobalProjectConfig::default()
         },
         &[],
+        &[],
+        None,
     )
     .unwrap();
 
@@ -224,9 +236,11 @@ mod tasks {
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            persistent: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
@@ -237,9 +251,11 @@ mod tasks {
             merge_env: Some(strategy.clone()),
             merge_inputs: Some(strategy.clone()),
             merge_outputs: Some(strategy),
+            persistent: None,
             retry_count: None,
             run_in_ci: None,
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
@@ -250,9 +266,11 @@ mod tasks {
             merge_env: None,
             merge_inputs: None,
             merge_outputs: None,
+            persistent: None,
             retry_count: Some(1),
             run_in_ci: Some(true),
             run_from_workspace_root: None,
+            timeout: None,
         }
     }
 
@@ -291,6 +309,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -335,6 +355,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -406,6 +428,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &implicit_inputs,
+            &[],
+            None,
         )
         .unwrap();
 
@@ -466,6 +490,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -541,6 +567,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -619,6 +647,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -697,6 +727,8 @@ mod tasks {
                 ..GlobalProjectConfig::default()
             },
             &[],
+            &[],
+            None,
         )
         .unwrap();
 
@@ -720,9 +752,11 @@ mod tasks {
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                persistent: None,
                                 retry_count: None,
                                 run_in_ci: None,
                                 run_from_workspace_root: None,
+                                timeout: None,
                             },
                             type_of: TaskType::Node,
                         }
@@ -749,9 +783,11 @@ mod tasks {
                                 merge_env: Some(TaskMergeStrategy::Replace),
                                 merge_inputs: Some(TaskMergeStrategy::Replace),
                                 merge_outputs: Some(TaskMergeStrategy::Append),
+                                persistent: None,
                                 retry_count: Some(1),
                                 run_in_ci: Some(true),
                                 run_from_workspace_root: None,
+                                timeout: None,
                             },
                             type_of: TaskType::Node,
                         },
@@ -777,6 +813,8 @@ mod tasks {
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -794,6 +832,8 @@ mod tasks {
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -811,6 +851,8 @@ mod tasks {
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -828,6 +870,8 @@ mod tasks {
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -846,6 +890,23 @@ mod tasks {
                 &get_fixtures_dir("task-deps"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
+            )
+            .unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "Target(NoProjectTagInTaskDeps(\"#frontend:build\"))")]
+        fn errors_for_tag_scope() {
+            Project::new(
+                "id",
+                "tag",
+                &get_fixtures_dir("task-deps"),
+                &mock_global_project_config(),
+                &[],
+                &[],
+                None,
             )
             .unwrap();
         }
@@ -885,6 +946,8 @@ mod tasks {
                     ..GlobalProjectConfig::default()
                 },
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -958,6 +1021,8 @@ mod tasks {
                     ..GlobalProjectConfig::default()
                 },
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1014,6 +1079,8 @@ mod tasks {
                     ..GlobalProjectConfig::default()
                 },
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1069,6 +1136,8 @@ mod tasks {
                     ..GlobalProjectConfig::default()
                 },
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1126,6 +1195,8 @@ mod tasks {
                     "/.moon/$taskType-$projectType.yml".to_owned(),
                     "*.yml".to_owned(),
                 ],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1149,6 +1220,44 @@ mod tasks {
 
             assert_eq!(a, b);
         }
+
+        #[test]
+        fn extracts_env_var_inputs() {
+            let workspace_root = get_fixtures_dir("base");
+            let project_root = workspace_root.join("files-and-dirs");
+            let project = Project::new(
+                "id",
+                "files-and-dirs",
+                &workspace_root,
+                &GlobalProjectConfig {
+                    tasks: HashMap::from([(
+                        String::from("test"),
+                        TaskConfig {
+                            command: Some(String::from("test")),
+                            inputs: Some(string_vec!["file.ts", "$NODE_ENV", "$API_URL"]),
+                            ..TaskConfig::default()
+                        },
+                    )]),
+                    ..GlobalProjectConfig::default()
+                },
+                &[],
+                &[],
+                None,
+            )
+            .unwrap();
+
+            let task = project.tasks.get("test").unwrap();
+
+            assert_eq!(
+                task.input_vars,
+                HashSet::from([String::from("NODE_ENV"), String::from("API_URL")])
+            );
+
+            assert_eq!(
+                task.input_paths,
+                HashSet::from([project_root.join("file.ts")])
+            );
+        }
     }
 }
 
@@ -1156,6 +1265,85 @@ mod workspace {
     use super::*;
     use moon_project::test::create_expanded_task;
 
+    mod inferred_tasks {
+        use super::*;
+        use std::collections::BTreeMap;
+
+        fn mock_scripts() -> BTreeMap<String, String> {
+            BTreeMap::from([
+                (String::from("build"), String::from("tsc")),
+                (String::from("lint"), String::from("eslint --fix")),
+                (String::from("prebuild"), String::from("rimraf lib")),
+                (String::from("test"), String::from("jest")),
+            ])
+        }
+
+        #[test]
+        fn creates_tasks_from_scripts() {
+            let project = Project::new(
+                "id",
+                "no-config",
+                &get_fixtures_dir("projects"),
+                &mock_global_project_config(),
+                &[],
+                &[],
+                Some(&mock_scripts()),
+            )
+            .unwrap();
+
+            let mut ids = project.tasks.keys().cloned().collect::<Vec<String>>();
+            ids.sort();
+
+            assert_eq!(ids, string_vec!["build", "lint", "prebuild", "test"]);
+
+            let build = project.get_task("build").unwrap();
+
+            assert_eq!(build.command, "tsc");
+            assert_eq!(build.deps, string_vec!["id:prebuild"]);
+            assert_eq!(build.type_of, TaskType::Node);
+        }
+
+        #[test]
+        fn merges_with_global_and_local_tasks() {
+            let project = Project::new(
+                "id",
+                "merge",
+                &get_fixtures_dir("task-inference"),
+                &GlobalProjectConfig {
+                    tasks: HashMap::from([(
+                        String::from("lint"),
+                        TaskConfig {
+                            command: Some(String::from("eslint")),
+                            args: Some(string_vec!["--cache"]),
+                            ..TaskConfig::default()
+                        },
+                    )]),
+                    ..GlobalProjectConfig::default()
+                },
+                &[],
+                &[],
+                Some(&mock_scripts()),
+            )
+            .unwrap();
+
+            let mut ids = project.tasks.keys().cloned().collect::<Vec<String>>();
+            ids.sort();
+
+            // `build` is excluded
+            assert_eq!(ids, string_vec!["lint", "prebuild", "test"]);
+
+            let lint = project.get_task("lint").unwrap();
+
+            assert_eq!(lint.command, "eslint");
+            assert_eq!(lint.args, string_vec!["--cache", "--fix"]);
+
+            let test = project.get_task("test").unwrap();
+
+            assert_eq!(test.command, "jest");
+            assert_eq!(test.args, string_vec!["--coverage"]);
+        }
+    }
+
     mod inherited_tasks {
         use super::*;
 
@@ -1203,6 +1391,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1217,6 +1407,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1231,6 +1423,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1245,6 +1439,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1259,6 +1455,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1273,6 +1471,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1291,6 +1491,8 @@ mod workspace {
                 &workspace_root,
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1315,6 +1517,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -1329,6 +1533,8 @@ mod workspace {
                 &get_fixtures_dir("task-inheritance"),
                 &mock_global_project_config(),
                 &[],
+                &[],
+                None,
             )
             .unwrap();
 
@@ -56,66 +56,18 @@ where
     }
 }
 
-#[track_caller]
-pub async fn link_file<T: AsRef<Path>>(from_root: T, from: T, to_root: T) -> Result<(), MoonError> {
-    let from_root = from_root.as_ref();
-    let from = from.as_ref();
-    let to_root = to_root.as_ref();
-    let to = to_root.join(from.strip_prefix(from_root).unwrap());
-
-    // Hardlink has already been created
-    if to.exists() {
-        return Ok(());
-    }
-
-    let to_dir = to.parent().unwrap();
-
-    if to_dir != to_root {
-        create_dir_all(to_dir).await?;
-    }
+pub async fn metadata<T: AsRef<Path>>(path: T) -> Result<std::fs::Metadata, MoonError> {
+    let path = path.as_ref();
 
-    fs::hard_link(from, &to)
+    fs::metadata(path)
         .await
-        .map_err(|_| MoonError::HardLink(from.to_path_buf(), to.clone()))?;
-
-    Ok(())
-}
-
-#[async_recursion]
-pub async fn link_dir<T: AsRef<Path> + Send>(
-    from_root: T,
-    from: T,
-    to_root: T,
-) -> Result<(), MoonError> {
-    let from_root = from_root.as_ref();
-    let from = from.as_ref();
-    let to_root = to_root.as_ref();
-    let entries = read_dir(from).await?;
-    let mut dirs = vec![];
-
-    // Link files before dirs incase an error occurs
-    for entry in entries {
-        let path = entry.path();
-
-        if path.is_file() {
-            link_file(from_root, &path, to_root).await?;
-        } else {
-            dirs.push(path);
-        }
-    }
-
-    // Link dirs in sequence for the same reason
-    for dir in dirs {
-        link_dir(from_root, &dir, to_root).await?;
-    }
-
-    Ok(())
+        .map_err(|e| map_io_to_fs_error(e, path.to_path_buf()))
 }
 
-pub async fn metadata<T: AsRef<Path>>(path: T) -> Result<std::fs::Metadata, MoonError> {
+pub async fn read<T: AsRef<Path>>(path: T) -> Result<String, MoonError> {
     let path = path.as_ref();
 
-    fs::metadata(path)
+    fs::read_to_string(path)
         .await
         .map_err(|e| map_io_to_fs_error(e, path.to_path_buf()))
 }
@@ -0,0 +1,106 @@
+// Renderers for graph formats that are shared between the project
+// and dependency graphs. Nodes are a tuple of ID and label, while
+// edges are a tuple of source ID, target ID, and type.
+
+pub type GraphNodes = Vec<(String, String)>;
+pub type GraphEdges = Vec<(String, String, String)>;
+
+fn escape_mermaid(value: &str) -> String {
+    value.replace('"', "#quot;")
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Format as a Mermaid flowchart, that can be embedded in Markdown.
+pub fn to_mermaid(nodes: &GraphNodes, edges: &GraphEdges) -> String {
+    let mut lines = vec![String::from("graph TD")];
+
+    for (id, label) in nodes {
+        lines.push(format!("    {}[\"{}\"]", id, escape_mermaid(label)));
+    }
+
+    for (source, target, _) in edges {
+        lines.push(format!("    {} --> {}", source, target));
+    }
+
+    lines.join("\n")
+}
+
+/// Format as a GraphML document, with the label of each node
+/// and the type of each edge as data attributes.
+pub fn to_graphml(nodes: &GraphNodes, edges: &GraphEdges) -> String {
+    let mut lines = vec![
+        String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#),
+        String::from(r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#),
+        String::from(r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#),
+        String::from(r#"  <key id="type" for="edge" attr.name="type" attr.type="string"/>"#),
+        String::from(r#"  <graph id="G" edgedefault="directed">"#),
+    ];
+
+    for (id, label) in nodes {
+        lines.push(format!(
+            r#"    <node id="{}"><data key="label">{}</data></node>"#,
+            escape_xml(id),
+            escape_xml(label)
+        ));
+    }
+
+    for (source, target, type_of) in edges {
+        lines.push(format!(
+            r#"    <edge source="{}" target="{}"><data key="type">{}</data></edge>"#,
+            escape_xml(source),
+            escape_xml(target),
+            escape_xml(type_of)
+        ));
+    }
+
+    lines.push(String::from("  </graph>"));
+    lines.push(String::from("</graphml>"));
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_graph() -> (GraphNodes, GraphEdges) {
+        (
+            vec![
+                ("n0".into(), "a".into()),
+                ("n1".into(), "b \"<quoted>\"".into()),
+            ],
+            vec![("n1".into(), "n0".into(), "dependsOn".into())],
+        )
+    }
+
+    #[test]
+    fn renders_mermaid() {
+        let (nodes, edges) = create_graph();
+
+        assert_eq!(
+            to_mermaid(&nodes, &edges),
+            "graph TD\n    n0[\"a\"]\n    n1[\"b #quot;<quoted>#quot;\"]\n    n1 --> n0"
+        );
+    }
+
+    #[test]
+    fn renders_graphml() {
+        let (nodes, edges) = create_graph();
+        let graphml = to_graphml(&nodes, &edges);
+
+        assert!(graphml.contains(r#"<node id="n0"><data key="label">a</data></node>"#));
+        assert!(graphml.contains(
+            r#"<node id="n1"><data key="label">b &quot;&lt;quoted&gt;&quot;</data></node>"#
+        ));
+        assert!(graphml
+            .contains(r#"<edge source="n1" target="n0"><data key="type">dependsOn</data></edge>"#));
+    }
+}
@@ -1,5 +1,6 @@
 pub mod fs;
 pub mod glob;
+pub mod graph;
 pub mod path;
 pub mod process;
 pub mod regex;
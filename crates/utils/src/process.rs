@@ -1,13 +1,18 @@
 use crate::path;
+use lazy_static::lazy_static;
 use moon_error::{map_io_to_process_error, MoonError};
 use moon_logger::{color, logging_enabled, trace};
+use std::collections::HashMap;
 use std::env;
 use std::ffi::OsStr;
+use std::fmt;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command as TokioCommand;
-use tokio::task;
+use tokio::{task, time};
 
 pub use shell_words::{join as join_args, split as split_args};
 pub use std::process::{ExitStatus, Output, Stdio};
@@ -57,6 +62,163 @@ pub fn output_to_trimmed_string(data: &[u8]) -> String {
     output_to_string(data).trim().to_owned()
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    Interrupt,
+    Kill,
+    Terminate,
+}
+
+impl Signal {
+    /// The conventional exit code for a process terminated by this signal (128 + N).
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Signal::Interrupt => 130,
+            Signal::Kill => 137,
+            Signal::Terminate => 143,
+        }
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Signal::Interrupt => write!(f, "SIGINT"),
+            Signal::Kill => write!(f, "SIGKILL"),
+            Signal::Terminate => write!(f, "SIGTERM"),
+        }
+    }
+}
+
+lazy_static! {
+    // Child processes that are currently running, mapped to whether they
+    // are the leader of their own process group. Required for forwarding signals.
+    static ref RUNNING_PROCESSES: Mutex<HashMap<u32, bool>> = Mutex::new(HashMap::new());
+}
+
+// Tracks a running child process until it has been dropped (completed).
+struct RunningProcess(Option<u32>);
+
+impl RunningProcess {
+    fn track(pid: Option<u32>, group_leader: bool) -> Self {
+        if let Some(id) = pid {
+            RUNNING_PROCESSES.lock().unwrap().insert(id, group_leader);
+        }
+
+        RunningProcess(pid)
+    }
+}
+
+impl Drop for RunningProcess {
+    fn drop(&mut self) {
+        if let Some(id) = self.0 {
+            RUNNING_PROCESSES.lock().unwrap().remove(&id);
+        }
+    }
+}
+
+/// Send the signal to all child processes that are currently running.
+#[cfg(unix)]
+pub fn signal_running_processes(signal: Signal) {
+    let sig = match signal {
+        Signal::Interrupt => libc::SIGINT,
+        Signal::Kill => libc::SIGKILL,
+        Signal::Terminate => libc::SIGTERM,
+    };
+
+    for (pid, group_leader) in RUNNING_PROCESSES.lock().unwrap().iter() {
+        trace!(
+            target: "moon:utils:process",
+            "Sending {} to process {}",
+            signal,
+            pid
+        );
+
+        unsafe {
+            if *group_leader {
+                libc::killpg(*pid as libc::pid_t, sig);
+            } else {
+                libc::kill(*pid as libc::pid_t, sig);
+            }
+        }
+    }
+}
+
+/// Send the signal to all child processes that are currently running.
+/// Windows delivers Ctrl+C to the entire console, so only killing is supported.
+#[cfg(windows)]
+pub fn signal_running_processes(signal: Signal) {
+    if signal == Signal::Kill {
+        for (pid, group_leader) in RUNNING_PROCESSES.lock().unwrap().iter() {
+            kill_process_tree(*pid, *group_leader);
+        }
+    }
+}
+
+// Find all descendants of the process by walking the parent IDs of every running
+// process, as they may not share a process group that can be killed in one call.
+#[cfg(unix)]
+fn find_descendant_pids(pid: u32) -> Vec<u32> {
+    let output = match std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid="])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return vec![],
+    };
+
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let mut parts = line.split_whitespace().map(|part| part.parse::<u32>());
+
+        if let (Some(Ok(child)), Some(Ok(parent))) = (parts.next(), parts.next()) {
+            children.entry(parent).or_default().push(child);
+        }
+    }
+
+    let mut descendants = vec![];
+    let mut queue = vec![pid];
+
+    while let Some(parent) = queue.pop() {
+        if let Some(ids) = children.get(&parent) {
+            descendants.extend(ids);
+            queue.extend(ids);
+        }
+    }
+
+    descendants
+}
+
+// When the child is the leader of its own process group (see `Command.new_process_group`),
+// kill the entire group. Otherwise it shares our group (an interactive process),
+// so kill it and each of its descendants individually, so that none are orphaned.
+#[cfg(unix)]
+fn kill_process_tree(pid: u32, group_leader: bool) {
+    if group_leader {
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+
+        return;
+    }
+
+    let descendants = find_descendant_pids(pid);
+
+    for id in std::iter::once(pid).chain(descendants) {
+        unsafe {
+            libc::kill(id as libc::pid_t, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn kill_process_tree(pid: u32, _group_leader: bool) {
+    let _ = std::process::Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/T", "/F"])
+        .output();
+}
+
 pub struct Command {
     bin: String,
 
@@ -64,6 +226,12 @@ pub struct Command {
 
     /// Convert non-zero exits to errors.
     error: bool,
+
+    /// Whether the child is the leader of its own process group.
+    process_group: bool,
+
+    /// Kill the process and its children when this duration has been exceeded.
+    timeout: Option<Duration>,
 }
 
 // This is rather annoying that we have to re-implement all these methods,
@@ -95,6 +263,8 @@ impl Command {
             bin: bin_name,
             cmd,
             error: true,
+            process_group: false,
+            timeout: None,
         }
     }
 
@@ -139,10 +309,24 @@ impl Command {
     pub async fn exec_capture_output(&mut self) -> Result<Output, MoonError> {
         self.log_command_info(None);
 
-        let output = self.cmd.output();
-        let output = output
-            .await
+        let child = self
+            .cmd
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
+        let pid = child.id();
+        let _running = RunningProcess::track(pid, self.process_group);
+
+        let output = self
+            .wait_with_timeout(pid, async {
+                child
+                    .wait_with_output()
+                    .await
+                    .map_err(|e| map_io_to_process_error(e, &self.bin))
+            })
+            .await?;
 
         self.handle_nonzero_status(&output)?;
 
@@ -163,6 +347,7 @@ impl Command {
             .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
+        let _running = RunningProcess::track(child.id(), self.process_group);
 
         let mut stdin = child.stdin.take().unwrap();
         stdin.write_all(input.as_bytes()).await.unwrap();
@@ -181,10 +366,13 @@ impl Command {
     pub async fn exec_stream_output(&mut self) -> Result<ExitStatus, MoonError> {
         self.log_command_info(None);
 
-        let status = self
+        let mut child = self
             .cmd
             .spawn()
-            .map_err(|e| map_io_to_process_error(e, &self.bin))?
+            .map_err(|e| map_io_to_process_error(e, &self.bin))?;
+        let _running = RunningProcess::track(child.id(), self.process_group);
+
+        let status = child
             .wait()
             .await
             .map_err(|e| map_io_to_process_error(e, &self.bin))?;
@@ -219,6 +407,8 @@ impl Command {
         // this *real ugly* implementation to solve it. There's gotta be a
         // better way to do this?
         // https://stackoverflow.com/a/49063262
+        let pid = child.id();
+        let _running = RunningProcess::track(pid, self.process_group);
         let stderr = BufReader::new(child.stderr.take().unwrap());
         let stdout = BufReader::new(child.stdout.take().unwrap());
         let mut handles = vec![];
@@ -275,15 +465,19 @@ impl Command {
                 .extend(captured_lines);
         }));
 
-        for handle in handles {
-            handle.await.expect("Failed to capture stdout/stderr");
-        }
-
         // Attempt to capture the child output
-        let mut output = child
-            .wait_with_output()
-            .await
-            .map_err(|e| map_io_to_process_error(e, &self.bin))?;
+        let mut output = self
+            .wait_with_timeout(pid, async {
+                for handle in handles {
+                    handle.await.expect("Failed to capture stdout/stderr");
+                }
+
+                child
+                    .wait_with_output()
+                    .await
+                    .map_err(|e| map_io_to_process_error(e, &self.bin))
+            })
+            .await?;
 
         if output.stderr.is_empty() {
             output.stderr = captured_stderr.read().unwrap().join("\n").into_bytes();
@@ -336,6 +530,38 @@ impl Command {
         self
     }
 
+    /// Spawn the child as the leader of a new process group, so that signals
+    /// can be sent to its entire tree. The child will no longer receive
+    /// signals from the terminal directly, so avoid for interactive processes.
+    pub fn new_process_group(&mut self) -> &mut Command {
+        if self.process_group {
+            return self;
+        }
+
+        self.process_group = true;
+
+        #[cfg(unix)]
+        unsafe {
+            self.cmd.pre_exec(|| {
+                if libc::setpgid(0, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+
+                Ok(())
+            });
+        }
+
+        self
+    }
+
+    /// Terminate the process, and all of its children, if it has not
+    /// completed within the provided duration. The child is intentionally not
+    /// killed on drop, as its descendants can only be found while it's alive.
+    pub fn timeout(&mut self, duration: Duration) -> &mut Command {
+        self.timeout = Some(duration);
+        self
+    }
+
     pub fn output_to_error(&self, output: &Output, with_message: bool) -> MoonError {
         let code = output.status.code().unwrap_or(-1);
 
@@ -348,6 +574,38 @@ impl Command {
         MoonError::ProcessNonZeroWithOutput(self.bin.clone(), code, message)
     }
 
+    async fn wait_with_timeout<T>(
+        &self,
+        pid: Option<u32>,
+        future: impl Future<Output = Result<T, MoonError>>,
+    ) -> Result<T, MoonError> {
+        let duration = match self.timeout {
+            Some(duration) => duration,
+            None => return future.await,
+        };
+
+        match time::timeout(duration, future).await {
+            Ok(result) => result,
+            Err(_) => {
+                trace!(
+                    target: "moon:utils:process",
+                    "Process {} timed out after {:?}, killing it and its children",
+                    color::shell(&self.bin),
+                    duration
+                );
+
+                if let Some(pid) = pid {
+                    kill_process_tree(pid, self.process_group);
+                }
+
+                Err(MoonError::ProcessTimeout(
+                    self.bin.clone(),
+                    duration.as_secs(),
+                ))
+            }
+        }
+    }
+
     fn handle_nonzero_status(&self, output: &Output) -> Result<(), MoonError> {
         if self.error && !output.status.success() {
             return Err(self.output_to_error(output, true));
@@ -400,3 +658,75 @@ impl Command {
         );
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    mod timeout {
+        use super::*;
+
+        #[tokio::test]
+        async fn errors_when_exceeded() {
+            let result = Command::new("sleep")
+                .arg("5")
+                .timeout(Duration::from_secs(1))
+                .exec_capture_output()
+                .await;
+
+            assert!(matches!(result, Err(MoonError::ProcessTimeout(_, 1))));
+        }
+
+        #[tokio::test]
+        async fn errors_when_exceeded_while_streaming() {
+            let result = Command::new("sleep")
+                .arg("5")
+                .timeout(Duration::from_secs(1))
+                .exec_stream_and_capture_output(None)
+                .await;
+
+            assert!(matches!(result, Err(MoonError::ProcessTimeout(_, 1))));
+        }
+
+        #[tokio::test]
+        async fn kills_children_when_not_a_process_group() {
+            let dir = assert_fs::TempDir::new().unwrap();
+            let marker = dir.path().join("marker");
+
+            let result = Command::new("sh")
+                .arg("-c")
+                .arg(format!("(sleep 2 && touch {}) & wait", marker.display()))
+                .timeout(Duration::from_secs(1))
+                .exec_capture_output()
+                .await;
+
+            assert!(matches!(result, Err(MoonError::ProcessTimeout(_, 1))));
+
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            assert!(!marker.exists());
+
+            dir.close().unwrap();
+        }
+
+        #[tokio::test]
+        async fn doesnt_create_a_process_group() {
+            let mut command = Command::new("sleep");
+            command.timeout(Duration::from_secs(1));
+
+            assert!(!command.process_group);
+        }
+
+        #[tokio::test]
+        async fn passes_when_not_exceeded() {
+            let output = Command::new("echo")
+                .arg("hello")
+                .timeout(Duration::from_secs(5))
+                .exec_capture_output()
+                .await
+                .unwrap();
+
+            assert_eq!(output_to_trimmed_string(&output.stdout), "hello");
+        }
+    }
+}
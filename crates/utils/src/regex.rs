@@ -9,8 +9,9 @@ lazy_static! {
     static ref ID_CLEAN: Regex = Regex::new("[^a-z0-9_-]+").unwrap();
 
     pub static ref ID_PATTERN: Regex = Regex::new(&format!("^{}$", *ID_GROUP)).unwrap();
+    pub static ref TAG_PATTERN: Regex = Regex::new("^[A-Za-z]{1}[0-9A-Za-z_:-]*$").unwrap();
     pub static ref TARGET_PATTERN: Regex = Regex::new(
-        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*)$").unwrap();
+        "^(?P<project>(?:[A-Za-z]{1}[0-9A-Za-z_-]*|#[A-Za-z]{1}[0-9A-Za-z_:-]*|\\^|~))?:(?P<task>[A-Za-z]{1}[0-9A-Za-z_-]*)$").unwrap();
 
     // Token function: `@func(arg)`
     static ref TOKEN_GROUP: &'static str = "([0-9A-Za-z_-]+)";
@@ -18,6 +19,9 @@ lazy_static! {
     pub static ref TOKEN_FUNC_PATTERN: Regex = Regex::new(&format!("^@([a-z]+)\\({}\\)$", *TOKEN_GROUP)).unwrap();
     pub static ref TOKEN_FUNC_ANYWHERE_PATTERN: Regex = Regex::new(&format!("@([a-z]+)\\({}\\)", *TOKEN_GROUP)).unwrap();
     pub static ref TOKEN_VAR_PATTERN: Regex = Regex::new("\\$(language|projectRoot|projectSource|projectType|project|target|taskType|task|workspaceRoot)").unwrap();
+
+    // Environment variable: `$NAME`
+    pub static ref ENV_VAR_PATTERN: Regex = Regex::new("^\\$([A-Z_][0-9A-Z_]*)$").unwrap();
 }
 
 pub fn create_regex(value: &str) -> Result<Regex, MoonError> {
@@ -28,10 +32,18 @@ pub fn clean_id(id: &str) -> String {
     ID_CLEAN.replace(id, "").to_string()
 }
 
+pub fn matches_env_var(value: &str) -> bool {
+    ENV_VAR_PATTERN.is_match(value)
+}
+
 pub fn matches_id(id: &str) -> bool {
     ID_PATTERN.is_match(id)
 }
 
+pub fn matches_tag(tag: &str) -> bool {
+    TAG_PATTERN.is_match(tag)
+}
+
 pub fn matches_target(target_id: &str) -> bool {
     TARGET_PATTERN.is_match(target_id)
 }
@@ -64,6 +64,101 @@ pub fn elapsed(duration: StdDuration) -> String {
     parts.join(" ")
 }
 
+/// Parse a human readable duration, composed of one or many amounts and units,
+/// for example "7d", "12h 30m", or "2 weeks". Returns `None` if invalid.
+pub fn parse_duration<T: AsRef<str>>(value: T) -> Option<StdDuration> {
+    let value = value.as_ref().trim();
+    let mut secs: u64 = 0;
+    let mut amount = String::new();
+    let mut unit = String::new();
+    let mut parts = vec![];
+
+    for ch in value.chars() {
+        if ch.is_ascii_digit() {
+            if !unit.is_empty() {
+                parts.push((amount.clone(), unit.clone()));
+                amount.clear();
+                unit.clear();
+            }
+
+            amount.push(ch);
+        } else if ch.is_alphabetic() {
+            unit.push(ch.to_ascii_lowercase());
+        } else if !ch.is_whitespace() {
+            return None;
+        }
+    }
+
+    if !amount.is_empty() {
+        parts.push((amount, unit));
+    }
+
+    if parts.is_empty() {
+        return None;
+    }
+
+    for (amount, unit) in parts {
+        let amount: u64 = amount.parse().ok()?;
+        let multiplier = match unit.as_str() {
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+            "d" | "day" | "days" => 86400,
+            "w" | "week" | "weeks" => 604_800,
+            _ => return None,
+        };
+
+        secs = secs.checked_add(amount.checked_mul(multiplier)?)?;
+    }
+
+    Some(StdDuration::from_secs(secs))
+}
+
 // pub fn relative(duration: Duration) -> String {
 //     format!("{}", HumanTime::from(duration))
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod parse_duration {
+        use super::*;
+
+        #[test]
+        fn parses_single_units() {
+            assert_eq!(parse_duration("30s"), Some(StdDuration::from_secs(30)));
+            assert_eq!(parse_duration("5m"), Some(StdDuration::from_secs(300)));
+            assert_eq!(parse_duration("2h"), Some(StdDuration::from_secs(7200)));
+            assert_eq!(parse_duration("7d"), Some(StdDuration::from_secs(604_800)));
+            assert_eq!(
+                parse_duration("1 week"),
+                Some(StdDuration::from_secs(604_800))
+            );
+        }
+
+        #[test]
+        fn parses_multiple_units() {
+            assert_eq!(
+                parse_duration("1d 12h"),
+                Some(StdDuration::from_secs(129_600))
+            );
+            assert_eq!(parse_duration("1h30m"), Some(StdDuration::from_secs(5400)));
+        }
+
+        #[test]
+        fn returns_none_when_invalid() {
+            assert_eq!(parse_duration(""), None);
+            assert_eq!(parse_duration("7"), None);
+            assert_eq!(parse_duration("days"), None);
+            assert_eq!(parse_duration("7 fortnights"), None);
+            assert_eq!(parse_duration("-7d"), None);
+        }
+
+        #[test]
+        fn returns_none_when_overflowing() {
+            assert_eq!(parse_duration("999999999999999d"), None);
+            assert_eq!(parse_duration("18446744073709551615s 1s"), None);
+        }
+    }
+}
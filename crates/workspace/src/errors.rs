@@ -40,6 +40,19 @@ pub enum WorkspaceError {
     )]
     InvalidGlobalProjectConfigFile(String),
 
+    #[error("Unable to find a hash manifest for <symbol>{0}</symbol>.")]
+    MissingHashManifest(String),
+
+    #[error(
+        "Multiple hash manifests found for <symbol>{0}</symbol>, please provide a longer hash."
+    )]
+    AmbiguousHashManifest(String),
+
+    #[error(
+        "Code owners file <file>{0}</file> is out of date. Run <shell>moon sync codeowners</shell> to regenerate it."
+    )]
+    StaleCodeowners(String),
+
     #[error(transparent)]
     Moon(#[from] MoonError),
 
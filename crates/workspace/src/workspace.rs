@@ -1,5 +1,5 @@
 use crate::errors::WorkspaceError;
-use moon_cache::CacheEngine;
+use moon_cache::{CacheEngine, RemoteCacheLoader};
 use moon_config::{constants, format_figment_errors, GlobalProjectConfig, WorkspaceConfig};
 use moon_lang_node::{package::PackageJson, tsconfig::TsConfigJson};
 use moon_logger::{color, debug, trace};
@@ -162,7 +162,8 @@ impl Workspace {
             load_tsconfig_json(&root_dir, &config.typescript.root_config_file_name).await?;
 
         // Setup components
-        let cache = CacheEngine::create(&root_dir).await?;
+        let mut cache = CacheEngine::create(&root_dir).await?;
+        cache.remote = RemoteCacheLoader::load(&root_dir, &config);
         let toolchain = Toolchain::create(&root_dir, &config).await?;
         let projects = ProjectGraph::create(&root_dir, &config, project_config, &cache).await?;
         let vcs = VcsLoader::load(&root_dir, &config)?;
@@ -14,9 +14,11 @@ export interface TaskOptions {
 	mergeEnv: TaskMergeStrategy;
 	mergeInputs: TaskMergeStrategy;
 	mergeOutputs: TaskMergeStrategy;
+	persistent: boolean;
 	retryCount: number;
 	runInCI: boolean;
 	runFromWorkspaceRoot: boolean;
+	timeout: number | null;
 }
 
 export interface Task {
@@ -27,6 +29,7 @@ export interface Task {
 	inputs: string[];
 	inputGlobs: string[];
 	inputPaths: string[];
+	inputVars: string[];
 	options: TaskOptions;
 	outputs: string[];
 	outputPaths: string[];
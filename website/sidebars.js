@@ -87,13 +87,16 @@ const sidebars = {
 				'commands/overview',
 				'commands/bin',
 				'commands/ci',
+				'commands/clean',
 				'commands/dep-graph',
 				'commands/init',
 				'commands/project',
 				'commands/project-graph',
 				'commands/query',
 				'commands/run',
+				'commands/run-many',
 				'commands/setup',
+				'commands/sync',
 				'commands/teardown',
 			],
 			link: {